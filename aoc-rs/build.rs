@@ -0,0 +1,47 @@
+use std::{env, fs, path::Path};
+
+/// With the `embed-inputs` feature, generates a table of every puzzle input in `../input` so
+/// the binary can run without them on disk. Days without input files are simply left out.
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    if env::var_os("CARGO_FEATURE_EMBED_INPUTS").is_none() {
+        return;
+    }
+
+    let input_dir = Path::new(&env::var("CARGO_MANIFEST_DIR").unwrap()).join("../input");
+    println!("cargo:rerun-if-changed={}", input_dir.display());
+
+    let mut files: Vec<_> = fs::read_dir(&input_dir)
+        .map(|entries| {
+            entries
+                .filter_map(|e| e.ok())
+                .map(|e| e.path())
+                .filter(|p| p.is_file())
+                .filter(|p| {
+                    p.file_name()
+                        .and_then(|n| n.to_str())
+                        .is_some_and(|n| n.starts_with("problem."))
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    files.sort();
+
+    let entries: String = files
+        .iter()
+        .map(|p| {
+            format!(
+                "    ({:?}, include_str!({:?})),\n",
+                p.file_name().unwrap().to_str().unwrap(),
+                fs::canonicalize(p).unwrap()
+            )
+        })
+        .collect();
+
+    let out = Path::new(&env::var("OUT_DIR").unwrap()).join("inputs.rs");
+    fs::write(
+        out,
+        format!("static EMBEDDED: &[(&str, &str)] = &[\n{}];\n", entries),
+    )
+    .unwrap();
+}
@@ -0,0 +1,245 @@
+//! Trees and graphs kept in a single `Vec`, with nodes referring to each other by index instead
+//! of through shared pointers. Links can go any way, parents and cycles included, without
+//! `Rc<RefCell<...>>`, and the whole structure is dropped at once.
+//!
+//! Nodes are never removed, so an id stays valid for as long as the arena it came from, and ids
+//! are handed out in the order nodes are added.
+
+use std::{
+    fmt::Debug,
+    hash::Hash,
+    marker::PhantomData,
+    ops::{Index, IndexMut},
+};
+
+/// The id of a node holding a `T`, only meaningful for the arena it came from
+pub struct NodeId<T> {
+    index: usize,
+    marker: PhantomData<fn() -> T>,
+}
+
+impl<T> NodeId<T> {
+    fn new(index: usize) -> Self {
+        Self {
+            index,
+            marker: PhantomData,
+        }
+    }
+
+    /// The position of the node in its arena, from 0, for keeping extra data per node in a `Vec`
+    pub fn index(&self) -> usize {
+        self.index
+    }
+}
+
+// written out rather than derived, which would ask the same of `T`
+impl<T> Clone for NodeId<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for NodeId<T> {}
+
+impl<T> PartialEq for NodeId<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.index == other.index
+    }
+}
+
+impl<T> Eq for NodeId<T> {}
+
+impl<T> PartialOrd for NodeId<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T> Ord for NodeId<T> {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.index.cmp(&other.index)
+    }
+}
+
+impl<T> Hash for NodeId<T> {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.index.hash(state);
+    }
+}
+
+impl<T> Debug for NodeId<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "NodeId({})", self.index)
+    }
+}
+
+#[derive(Debug, Clone)]
+struct TreeNode<T> {
+    value: T,
+    parent: Option<NodeId<T>>,
+    children: Vec<NodeId<T>>,
+}
+
+/// A rooted tree where every node knows its parent and its children, in the order they were
+/// added. A child is always added after its parent, so its id is always the greater one.
+#[derive(Debug, Clone)]
+pub struct Tree<T> {
+    nodes: Vec<TreeNode<T>>,
+}
+
+impl<T> Tree<T> {
+    pub fn new(root: T) -> Self {
+        Self {
+            nodes: vec![TreeNode {
+                value: root,
+                parent: None,
+                children: vec![],
+            }],
+        }
+    }
+
+    pub fn root(&self) -> NodeId<T> {
+        NodeId::new(0)
+    }
+
+    pub fn add_child(&mut self, parent: NodeId<T>, value: T) -> NodeId<T> {
+        let id = NodeId::new(self.nodes.len());
+        self.nodes.push(TreeNode {
+            value,
+            parent: Some(parent),
+            children: vec![],
+        });
+        self.nodes[parent.index].children.push(id);
+        id
+    }
+
+    /// `None` for the root
+    pub fn parent(&self, id: NodeId<T>) -> Option<NodeId<T>> {
+        self.nodes[id.index].parent
+    }
+
+    pub fn children(&self, id: NodeId<T>) -> impl DoubleEndedIterator<Item = NodeId<T>> + '_ {
+        self.nodes[id.index].children.iter().copied()
+    }
+
+    /// The first child of `id` matching `predicate`
+    pub fn find_child(
+        &self,
+        id: NodeId<T>,
+        mut predicate: impl FnMut(&T) -> bool,
+    ) -> Option<NodeId<T>> {
+        self.children(id).find(|&c| predicate(&self[c]))
+    }
+
+    /// `id` and everything below it, each node before its children
+    pub fn preorder(&self, id: NodeId<T>) -> impl Iterator<Item = NodeId<T>> + '_ {
+        let mut stack = vec![id];
+        std::iter::from_fn(move || {
+            let id = stack.pop()?;
+            stack.extend(self.children(id).rev());
+            Some(id)
+        })
+    }
+
+    /// Every node in the order it was added, so parents always come before their children
+    pub fn ids(&self) -> impl DoubleEndedIterator<Item = NodeId<T>> + ExactSizeIterator {
+        (0..self.nodes.len()).map(NodeId::new)
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Always false, a tree has at least its root
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+}
+
+impl<T> Index<NodeId<T>> for Tree<T> {
+    type Output = T;
+
+    fn index(&self, id: NodeId<T>) -> &Self::Output {
+        &self.nodes[id.index].value
+    }
+}
+
+impl<T> IndexMut<NodeId<T>> for Tree<T> {
+    fn index_mut(&mut self, id: NodeId<T>) -> &mut Self::Output {
+        &mut self.nodes[id.index].value
+    }
+}
+
+#[derive(Debug, Clone)]
+struct GraphNode<T> {
+    value: T,
+    edges: Vec<NodeId<T>>,
+}
+
+/// A directed graph, undirected edges are a pair of edges going opposite ways
+#[derive(Debug, Clone)]
+pub struct Graph<T> {
+    nodes: Vec<GraphNode<T>>,
+}
+
+impl<T> Graph<T> {
+    pub fn new() -> Self {
+        Self { nodes: vec![] }
+    }
+
+    pub fn add_node(&mut self, value: T) -> NodeId<T> {
+        let id = NodeId::new(self.nodes.len());
+        self.nodes.push(GraphNode {
+            value,
+            edges: vec![],
+        });
+        id
+    }
+
+    pub fn add_edge(&mut self, from: NodeId<T>, to: NodeId<T>) {
+        self.nodes[from.index].edges.push(to);
+    }
+
+    /// The nodes `id` has an edge to, in the order the edges were added
+    pub fn neighbors(&self, id: NodeId<T>) -> impl DoubleEndedIterator<Item = NodeId<T>> + '_ {
+        self.nodes[id.index].edges.iter().copied()
+    }
+
+    /// Every node in the order it was added
+    pub fn ids(&self) -> impl DoubleEndedIterator<Item = NodeId<T>> + ExactSizeIterator {
+        (0..self.nodes.len()).map(NodeId::new)
+    }
+
+    /// The first node matching `predicate`
+    pub fn find(&self, mut predicate: impl FnMut(&T) -> bool) -> Option<NodeId<T>> {
+        self.ids().find(|&id| predicate(&self[id]))
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+}
+
+impl<T> Default for Graph<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Index<NodeId<T>> for Graph<T> {
+    type Output = T;
+
+    fn index(&self, id: NodeId<T>) -> &Self::Output {
+        &self.nodes[id.index].value
+    }
+}
+
+impl<T> IndexMut<NodeId<T>> for Graph<T> {
+    fn index_mut(&mut self, id: NodeId<T>) -> &mut Self::Output {
+        &mut self.nodes[id.index].value
+    }
+}
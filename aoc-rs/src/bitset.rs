@@ -0,0 +1,162 @@
+use std::ops::{BitAnd, BitOr, Sub};
+
+/// A set of indices below 64 kept in a single word, cheap to copy, compare and hash
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct BitSet(u64);
+
+impl BitSet {
+    pub const CAPACITY: usize = u64::BITS as usize;
+
+    pub fn new() -> Self {
+        Self(0)
+    }
+
+    pub fn from_bits(bits: u64) -> Self {
+        Self(bits)
+    }
+
+    pub fn bits(&self) -> u64 {
+        self.0
+    }
+
+    fn bit(index: usize) -> u64 {
+        assert!(index < Self::CAPACITY, "{} doesn't fit in a bit set", index);
+        1 << index
+    }
+
+    /// Outputs whether the index was newly added
+    pub fn insert(&mut self, index: usize) -> bool {
+        let added = !self.contains(index);
+        self.0 |= Self::bit(index);
+        added
+    }
+
+    /// Outputs whether the index was there to remove
+    pub fn remove(&mut self, index: usize) -> bool {
+        let removed = self.contains(index);
+        self.0 &= !Self::bit(index);
+        removed
+    }
+
+    /// A copy of the set with `index` added
+    pub fn with(mut self, index: usize) -> Self {
+        self.insert(index);
+        self
+    }
+
+    pub fn contains(&self, index: usize) -> bool {
+        self.0 & Self::bit(index) != 0
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    pub fn is_subset(&self, other: &BitSet) -> bool {
+        self.0 & !other.0 == 0
+    }
+
+    /// Everything in `universe` that isn't in the set
+    pub fn complement(&self, universe: BitSet) -> Self {
+        universe - *self
+    }
+
+    /// The indices in ascending order
+    pub fn iter(&self) -> Iter {
+        Iter(self.0)
+    }
+
+    /// Every subset, from the full set down to the empty one, without allocating
+    pub fn subsets(&self) -> Subsets {
+        Subsets {
+            set: self.0,
+            next: Some(self.0),
+        }
+    }
+}
+
+impl BitOr for BitSet {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self::Output {
+        Self(self.0 | rhs.0)
+    }
+}
+
+impl BitAnd for BitSet {
+    type Output = Self;
+
+    fn bitand(self, rhs: Self) -> Self::Output {
+        Self(self.0 & rhs.0)
+    }
+}
+
+impl Sub for BitSet {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self(self.0 & !rhs.0)
+    }
+}
+
+impl FromIterator<usize> for BitSet {
+    fn from_iter<I: IntoIterator<Item = usize>>(iter: I) -> Self {
+        iter.into_iter().fold(Self::new(), BitSet::with)
+    }
+}
+
+impl IntoIterator for BitSet {
+    type Item = usize;
+    type IntoIter = Iter;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+pub struct Iter(u64);
+
+impl Iterator for Iter {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.0 == 0 {
+            return None;
+        }
+
+        let index = self.0.trailing_zeros() as usize;
+        // clear the lowest bit
+        self.0 &= self.0 - 1;
+        Some(index)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.0.count_ones() as usize;
+        (len, Some(len))
+    }
+}
+
+impl ExactSizeIterator for Iter {}
+
+pub struct Subsets {
+    set: u64,
+    next: Option<u64>,
+}
+
+impl Iterator for Subsets {
+    type Item = BitSet;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let subset = self.next?;
+        // counting down within the set's bits, the empty set is last
+        self.next = match subset {
+            0 => None,
+            _ => Some((subset - 1) & self.set),
+        };
+        Some(BitSet(subset))
+    }
+}
@@ -0,0 +1,25 @@
+//! Named work counters, e.g. states expanded or cache hits, that solvers bump while they run.
+//! The runner resets them before each solve and reports whatever was counted.
+
+use std::{cell::RefCell, collections::BTreeMap};
+
+thread_local! {
+    static COUNTERS: RefCell<BTreeMap<&'static str, u64>> = const { RefCell::new(BTreeMap::new()) };
+}
+
+pub fn increment(name: &'static str) {
+    add(name, 1);
+}
+
+pub fn add(name: &'static str, n: u64) {
+    COUNTERS.with(|c| *c.borrow_mut().entry(name).or_insert(0) += n);
+}
+
+pub fn reset() {
+    COUNTERS.with(|c| c.borrow_mut().clear());
+}
+
+/// Outputs every counter sorted by name and resets them
+pub fn take() -> Vec<(&'static str, u64)> {
+    COUNTERS.with(|c| std::mem::take(&mut *c.borrow_mut()).into_iter().collect())
+}
@@ -0,0 +1,94 @@
+//! Cycle detection for simulations too long to run to the end. The simulation reports a key for
+//! its state after every step; once a key repeats, everything from then on repeats too, and any
+//! quantity recorded along the way can be extrapolated to a far later step.
+
+use std::{collections::HashMap, hash::Hash};
+
+/// A stretch of steps that repeats forever: the state after `start + length` steps is the same as
+/// after `start` steps
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Cycle {
+    pub start: usize,
+    pub length: usize,
+}
+
+impl Cycle {
+    /// The step in the first lap of the cycle with the same state as `step`
+    pub fn equivalent_step(&self, step: u64) -> usize {
+        match step < self.start as u64 {
+            true => step as usize,
+            false => self.start + ((step - self.start as u64) % self.length as u64) as usize,
+        }
+    }
+
+    /// Extrapolates a quantity that grows by the same amount every lap, e.g. a running total.
+    /// `history[i]` is the quantity after `i` steps, and must go at least as far as
+    /// `start + length`.
+    pub fn extrapolate(&self, history: &[i64], step: u64) -> i64 {
+        if let Some(&value) = history.get(step as usize) {
+            return value;
+        }
+
+        let laps = ((step - self.start as u64) / self.length as u64) as i64;
+        let per_lap = history[self.start + self.length] - history[self.start];
+        history[self.equivalent_step(step)] + laps * per_lap
+    }
+}
+
+/// Remembers the key of every step so far. Keys are hashed rather than compared pairwise, so
+/// each step costs the same however long the simulation has run.
+#[derive(Debug, Clone)]
+pub struct CycleDetector<K> {
+    seen: HashMap<K, usize>,
+    steps: usize,
+}
+
+impl<K: Hash + Eq> CycleDetector<K> {
+    pub fn new() -> Self {
+        Self {
+            seen: HashMap::new(),
+            steps: 0,
+        }
+    }
+
+    /// Records the key for the state after the next step, starting with the initial state at
+    /// step 0. Outputs the cycle once a key has been seen before.
+    pub fn observe(&mut self, key: K) -> Option<Cycle> {
+        let step = self.steps;
+        self.steps += 1;
+        self.seen.insert(key, step).map(|start| Cycle {
+            start,
+            length: step - start,
+        })
+    }
+}
+
+impl<K: Hash + Eq> Default for CycleDetector<K> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Runs `step` on `state` until `key` repeats or `limit` steps have run, recording `measure`
+/// before the first step and after each one. Outputs the cycle, if one was found, with the
+/// measurements.
+pub fn run<S, K: Hash + Eq, M>(
+    state: &mut S,
+    limit: usize,
+    mut step: impl FnMut(&mut S),
+    mut key: impl FnMut(&S) -> K,
+    mut measure: impl FnMut(&S) -> M,
+) -> (Option<Cycle>, Vec<M>) {
+    let mut detector = CycleDetector::new();
+    let mut history = vec![];
+    for i in 0..=limit {
+        history.push(measure(state));
+        if let Some(cycle) = detector.observe(key(state)) {
+            return (Some(cycle), history);
+        }
+        if i < limit {
+            step(state);
+        }
+    }
+    (None, history)
+}
@@ -0,0 +1,159 @@
+use std::{borrow::Borrow, collections::HashMap, hash::Hash};
+
+use crate::search::{self, Cost};
+
+/// The shortest distance between every pair of nodes in a graph, `None` when there's no way
+/// from one to the other. Nodes are kept in the order they were given, so they can also be
+/// looked up by index.
+#[derive(Debug, Clone)]
+pub struct DistanceMatrix<L, C> {
+    labels: Vec<L>,
+    indices: HashMap<L, usize>,
+    distances: Vec<Option<C>>,
+    /// The node just before the last on the shortest path from one node to another
+    previous: Vec<Option<usize>>,
+}
+
+impl<L: Clone + Eq + Hash, C: Cost> DistanceMatrix<L, C> {
+    fn unconnected(labels: impl IntoIterator<Item = L>) -> Self {
+        let labels: Vec<L> = labels.into_iter().collect();
+        let indices = labels
+            .iter()
+            .enumerate()
+            .map(|(i, l)| (l.clone(), i))
+            .collect();
+        let n = labels.len();
+
+        let mut matrix = Self {
+            labels,
+            indices,
+            distances: vec![None; n * n],
+            previous: vec![None; n * n],
+        };
+        for i in 0..n {
+            matrix.distances[i * n + i] = Some(C::default());
+        }
+        matrix
+    }
+
+    /// Runs Floyd-Warshall over directed, weighted `edges`. Panics if an edge uses a label that
+    /// isn't in `labels`.
+    pub fn floyd_warshall(
+        labels: impl IntoIterator<Item = L>,
+        edges: impl IntoIterator<Item = (L, L, C)>,
+    ) -> Self {
+        let mut matrix = Self::unconnected(labels);
+        let n = matrix.len();
+        for (from, to, weight) in edges {
+            let (i, j) = (matrix.indices[&from], matrix.indices[&to]);
+            if i != j && matrix.distances[i * n + j].is_none_or(|d| weight < d) {
+                matrix.distances[i * n + j] = Some(weight);
+                matrix.previous[i * n + j] = Some(i);
+            }
+        }
+
+        for k in 0..n {
+            for i in 0..n {
+                let Some(ik) = matrix.distances[i * n + k] else {
+                    continue;
+                };
+                for j in 0..n {
+                    let Some(kj) = matrix.distances[k * n + j] else {
+                        continue;
+                    };
+                    if matrix.distances[i * n + j].is_none_or(|ij| ik + kj < ij) {
+                        matrix.distances[i * n + j] = Some(ik + kj);
+                        matrix.previous[i * n + j] = matrix.previous[k * n + j];
+                    }
+                }
+            }
+        }
+
+        matrix
+    }
+
+    /// Runs a breadth-first search from every node, cheaper than Floyd-Warshall on sparse graphs
+    /// where every edge has the same length of 1. Panics if `neighbors` outputs a label that isn't
+    /// in `labels`.
+    pub fn bfs<I>(labels: impl IntoIterator<Item = L>, mut neighbors: impl FnMut(&L) -> I) -> Self
+    where
+        I: IntoIterator<Item = L>,
+        C: TryFrom<usize>,
+    {
+        let mut matrix = Self::unconnected(labels);
+        let n = matrix.len();
+        for i in 0..n {
+            let search = search::bfs([matrix.labels[i].clone()], &mut neighbors, |_| false);
+            for (label, steps) in search.costs() {
+                let j = matrix.indices[label];
+                let steps = C::try_from(steps).ok().expect("distance too large");
+                matrix.distances[i * n + j] = Some(steps);
+                matrix.previous[i * n + j] = search.parent(label).map(|p| matrix.indices[p]);
+            }
+        }
+
+        matrix
+    }
+
+    pub fn len(&self) -> usize {
+        self.labels.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.labels.is_empty()
+    }
+
+    pub fn labels(&self) -> &[L] {
+        &self.labels
+    }
+
+    pub fn label(&self, index: usize) -> &L {
+        &self.labels[index]
+    }
+
+    pub fn index<Q>(&self, label: &Q) -> Option<usize>
+    where
+        L: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.indices.get(label).copied()
+    }
+
+    /// The distance between two nodes by index
+    pub fn get(&self, from: usize, to: usize) -> Option<C> {
+        self.distances[from * self.len() + to]
+    }
+
+    /// The distance between two nodes by label, `None` if either is unknown or there's no way
+    /// between them
+    pub fn distance<Q>(&self, from: &Q, to: &Q) -> Option<C>
+    where
+        L: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.get(self.index(from)?, self.index(to)?)
+    }
+
+    /// The indices along a shortest path between two nodes, both included
+    pub fn path_indices(&self, from: usize, to: usize) -> Option<Vec<usize>> {
+        self.get(from, to)?;
+
+        let mut path = vec![to];
+        while *path.last().unwrap() != from {
+            let previous = self.previous[from * self.len() + path.last().unwrap()]?;
+            path.push(previous);
+        }
+        path.reverse();
+        Some(path)
+    }
+
+    /// The labels along a shortest path between two nodes, both included
+    pub fn path<Q>(&self, from: &Q, to: &Q) -> Option<Vec<L>>
+    where
+        L: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let path = self.path_indices(self.index(from)?, self.index(to)?)?;
+        Some(path.into_iter().map(|i| self.labels[i].clone()).collect())
+    }
+}
@@ -0,0 +1,296 @@
+use std::ops::{Index, IndexMut};
+
+use crate::point::Point;
+
+/// A dense, row-major grid. `(0, 0)` is the first character of the first line and y grows
+/// downwards, the same way the puzzle inputs are written.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T: Clone> Grid<T> {
+    pub fn new(width: usize, height: usize, fill: T) -> Self {
+        Self {
+            width,
+            height,
+            cells: vec![fill; width * height],
+        }
+    }
+
+    /// Like `from_rows`, but short rows are padded with `fill` to the length of the longest
+    pub fn from_ragged(mut rows: Vec<Vec<T>>, fill: T) -> Self {
+        let width = rows.iter().map(Vec::len).max().unwrap_or(0);
+        for row in rows.iter_mut() {
+            row.resize(width, fill.clone());
+        }
+        Self::from_rows(rows)
+    }
+
+    /// A new grid with `transform` applied, see `view` to avoid cloning the cells
+    pub fn transformed(&self, transform: Transform) -> Self {
+        let view = self.view(transform);
+        Self {
+            width: view.width(),
+            height: view.height(),
+            cells: view.rows().flatten().cloned().collect(),
+        }
+    }
+}
+
+impl<T> Grid<T> {
+    /// Panics if the rows aren't all the same length
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Self {
+        let height = rows.len();
+        let width = rows.first().map_or(0, |r| r.len());
+        assert!(
+            rows.iter().all(|r| r.len() == width),
+            "grid rows must all be the same length"
+        );
+
+        Self {
+            width,
+            height,
+            cells: rows.into_iter().flatten().collect(),
+        }
+    }
+
+    /// Builds a grid from lines of characters, mapping each character to a cell
+    pub fn parse<S: AsRef<str>, F: FnMut(char) -> T>(lines: &[S], mut f: F) -> Self {
+        Self::from_rows(
+            lines
+                .iter()
+                .map(|l| l.as_ref().chars().map(&mut f).collect())
+                .collect(),
+        )
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, p: Point) -> bool {
+        p.x() >= 0 && p.y() >= 0 && (p.x() as usize) < self.width && (p.y() as usize) < self.height
+    }
+
+    pub fn get(&self, p: Point) -> Option<&T> {
+        match self.contains(p) {
+            true => Some(&self.cells[self.offset(p)]),
+            false => None,
+        }
+    }
+
+    pub fn get_mut(&mut self, p: Point) -> Option<&mut T> {
+        match self.contains(p) {
+            true => {
+                let offset = self.offset(p);
+                Some(&mut self.cells[offset])
+            }
+            false => None,
+        }
+    }
+
+    pub fn row(&self, y: usize) -> &[T] {
+        &self.cells[(y * self.width)..((y + 1) * self.width)]
+    }
+
+    pub fn rows(&self) -> impl DoubleEndedIterator<Item = &[T]> + ExactSizeIterator {
+        (0..self.height).map(move |y| self.row(y))
+    }
+
+    pub fn column(&self, x: usize) -> impl DoubleEndedIterator<Item = &T> + ExactSizeIterator {
+        (0..self.height).map(move |y| &self.cells[y * self.width + x])
+    }
+
+    pub fn columns(
+        &self,
+    ) -> impl DoubleEndedIterator<Item = impl DoubleEndedIterator<Item = &T> + ExactSizeIterator>
+           + ExactSizeIterator {
+        (0..self.width).map(move |x| self.column(x))
+    }
+
+    /// Every point in the grid, row by row
+    pub fn points(&self) -> impl Iterator<Item = Point> {
+        let (width, height) = (self.width as i32, self.height as i32);
+        (0..height).flat_map(move |y| (0..width).map(move |x| Point::new(x, y)))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Point, &T)> {
+        self.points().zip(self.cells.iter())
+    }
+
+    /// The first point, row by row, whose cell matches `predicate`
+    pub fn position<F: FnMut(&T) -> bool>(&self, mut predicate: F) -> Option<Point> {
+        self.iter().find(|(_, t)| predicate(t)).map(|(p, _)| p)
+    }
+
+    pub fn map<U, F: FnMut(&T) -> U>(&self, f: F) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+
+    /// The cells in row-major order
+    pub fn as_slice(&self) -> &[T] {
+        &self.cells
+    }
+
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        &mut self.cells
+    }
+
+    /// The grid as if `transform` had been applied, reading through to this one
+    pub fn view(&self, transform: Transform) -> View<'_, T> {
+        View {
+            grid: self,
+            transform,
+        }
+    }
+
+    fn offset(&self, p: Point) -> usize {
+        p.y() as usize * self.width + p.x() as usize
+    }
+}
+
+/// The ways to turn a grid over without changing its cells. Rotations are as seen on screen,
+/// with y growing downwards.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Transform {
+    Identity,
+    RotateCw,
+    Rotate180,
+    RotateCcw,
+    /// Mirrors left to right
+    FlipHorizontal,
+    /// Mirrors top to bottom
+    FlipVertical,
+    /// Rows become columns
+    Transpose,
+}
+
+impl Transform {
+    /// Whether the width and height swap
+    fn swaps_axes(self) -> bool {
+        matches!(
+            self,
+            Transform::RotateCw | Transform::RotateCcw | Transform::Transpose
+        )
+    }
+}
+
+/// A transformed grid that reads from the original, see `Grid::view`
+#[derive(Debug)]
+pub struct View<'a, T> {
+    grid: &'a Grid<T>,
+    transform: Transform,
+}
+
+// derived, these would need `T: Copy`
+impl<T> Clone for View<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for View<'_, T> {}
+
+impl<'a, T> View<'a, T> {
+    pub fn width(&self) -> usize {
+        match self.transform.swaps_axes() {
+            true => self.grid.height,
+            false => self.grid.width,
+        }
+    }
+
+    pub fn height(&self) -> usize {
+        match self.transform.swaps_axes() {
+            true => self.grid.width,
+            false => self.grid.height,
+        }
+    }
+
+    pub fn contains(&self, p: Point) -> bool {
+        p.x() >= 0
+            && p.y() >= 0
+            && (p.x() as usize) < self.width()
+            && (p.y() as usize) < self.height()
+    }
+
+    /// The point in the original grid that shows up at `p`
+    fn source(&self, p: Point) -> Point {
+        let (w, h) = (self.grid.width as i32, self.grid.height as i32);
+        let (x, y) = (p.x(), p.y());
+        match self.transform {
+            Transform::Identity => p,
+            Transform::RotateCw => Point::new(y, h - 1 - x),
+            Transform::Rotate180 => Point::new(w - 1 - x, h - 1 - y),
+            Transform::RotateCcw => Point::new(w - 1 - y, x),
+            Transform::FlipHorizontal => Point::new(w - 1 - x, y),
+            Transform::FlipVertical => Point::new(x, h - 1 - y),
+            Transform::Transpose => Point::new(y, x),
+        }
+    }
+
+    pub fn get(&self, p: Point) -> Option<&'a T> {
+        match self.contains(p) {
+            true => self.grid.get(self.source(p)),
+            false => None,
+        }
+    }
+
+    pub fn rows(
+        &self,
+    ) -> impl DoubleEndedIterator<Item = impl DoubleEndedIterator<Item = &'a T> + ExactSizeIterator>
+           + ExactSizeIterator
+           + 'a {
+        let view = *self;
+        (0..view.height()).map(move |y| {
+            (0..view.width()).map(move |x| &view.grid[view.source(Point::new(x as i32, y as i32))])
+        })
+    }
+}
+
+impl<T> Index<Point> for View<'_, T> {
+    type Output = T;
+
+    fn index(&self, p: Point) -> &Self::Output {
+        self.get(p)
+            .unwrap_or_else(|| panic!("{:?} is outside the grid", p))
+    }
+}
+
+impl<T> Index<Point> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, p: Point) -> &Self::Output {
+        self.get(p)
+            .unwrap_or_else(|| panic!("{:?} is outside the grid", p))
+    }
+}
+
+impl<T> IndexMut<Point> for Grid<T> {
+    fn index_mut(&mut self, p: Point) -> &mut Self::Output {
+        self.get_mut(p)
+            .unwrap_or_else(|| panic!("{:?} is outside the grid", p))
+    }
+}
+
+impl<T: std::fmt::Display> std::fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for row in self.rows() {
+            for c in row {
+                write!(f, "{}", c)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,179 @@
+//! Pictures of puzzle states, written as binary PPM (color) or PGM (grayscale). Both are a short
+//! text header followed by raw pixel bytes, simple enough to write by hand and opened by most
+//! image viewers and converters.
+
+use std::{
+    fs::File,
+    io::{self, BufWriter, Write},
+    ops::{Index, IndexMut},
+    path::Path,
+};
+
+use crate::{
+    grid::{Grid, View},
+    point::Point,
+};
+
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Rgb(pub u8, pub u8, pub u8);
+
+impl Rgb {
+    pub const BLACK: Rgb = Rgb(0, 0, 0);
+    pub const WHITE: Rgb = Rgb(255, 255, 255);
+    pub const RED: Rgb = Rgb(255, 0, 0);
+    pub const GREEN: Rgb = Rgb(0, 255, 0);
+    pub const BLUE: Rgb = Rgb(0, 0, 255);
+    pub const YELLOW: Rgb = Rgb(255, 255, 0);
+
+    pub fn gray(level: u8) -> Self {
+        Self(level, level, level)
+    }
+
+    /// A gray going from black at `min` to white at `max`, values outside are clamped
+    pub fn shade(value: i64, min: i64, max: i64) -> Self {
+        if max <= min {
+            return Self::WHITE;
+        }
+        let value = value.clamp(min, max);
+        Self::gray(((value - min) * 255 / (max - min)) as u8)
+    }
+
+    pub fn is_gray(&self) -> bool {
+        self.0 == self.1 && self.1 == self.2
+    }
+
+    /// The perceived brightness, with the usual weights for sRGB
+    pub fn luma(&self) -> u8 {
+        let (r, g, b) = (self.0 as u32, self.1 as u32, self.2 as u32);
+        ((r * 299 + g * 587 + b * 114) / 1000) as u8
+    }
+}
+
+/// A picture made of `width` by `height` pixels, (0, 0) being the top left one
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Image {
+    width: usize,
+    height: usize,
+    pixels: Vec<Rgb>,
+}
+
+impl Image {
+    pub fn new(width: usize, height: usize, fill: Rgb) -> Self {
+        Self {
+            width,
+            height,
+            pixels: vec![fill; width * height],
+        }
+    }
+
+    /// Colors every pixel with `color`, called once for each in reading order
+    pub fn from_fn(width: usize, height: usize, mut color: impl FnMut(Point) -> Rgb) -> Self {
+        let pixels = (0..height)
+            .flat_map(|y| (0..width).map(move |x| Point::new(x as i32, y as i32)))
+            .map(&mut color)
+            .collect();
+        Self {
+            width,
+            height,
+            pixels,
+        }
+    }
+
+    /// One pixel per cell
+    pub fn from_grid<T>(grid: &Grid<T>, color: impl FnMut(&T) -> Rgb) -> Self {
+        Self {
+            width: grid.width(),
+            height: grid.height(),
+            pixels: grid.as_slice().iter().map(color).collect(),
+        }
+    }
+
+    /// One pixel per cell, as the view shows them
+    pub fn from_view<T>(view: View<'_, T>, color: impl FnMut(&T) -> Rgb) -> Self {
+        Self {
+            width: view.width(),
+            height: view.height(),
+            pixels: view.rows().flatten().map(color).collect(),
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, p: Point) -> bool {
+        (0..self.width as i32).contains(&p.x()) && (0..self.height as i32).contains(&p.y())
+    }
+
+    /// Every pixel becomes a `factor` by `factor` square, so small grids are big enough to see
+    pub fn scaled(&self, factor: usize) -> Self {
+        assert!(factor >= 1, "can't scale an image by {}", factor);
+        Self::from_fn(self.width * factor, self.height * factor, |p| {
+            self[Point::new(p.x() / factor as i32, p.y() / factor as i32)]
+        })
+    }
+
+    /// Whether saving as PGM would lose nothing
+    pub fn is_grayscale(&self) -> bool {
+        self.pixels.iter().all(Rgb::is_gray)
+    }
+
+    /// "pgm" when every pixel is gray, "ppm" otherwise
+    pub fn extension(&self) -> &'static str {
+        match self.is_grayscale() {
+            true => "pgm",
+            false => "ppm",
+        }
+    }
+
+    pub fn write_ppm(&self, out: &mut impl Write) -> io::Result<()> {
+        write!(out, "P6\n{} {}\n255\n", self.width, self.height)?;
+        let bytes: Vec<u8> = self.pixels.iter().flat_map(|p| [p.0, p.1, p.2]).collect();
+        out.write_all(&bytes)
+    }
+
+    /// Writes the brightness of each pixel, colors are turned to gray
+    pub fn write_pgm(&self, out: &mut impl Write) -> io::Result<()> {
+        write!(out, "P5\n{} {}\n255\n", self.width, self.height)?;
+        let bytes: Vec<u8> = self.pixels.iter().map(Rgb::luma).collect();
+        out.write_all(&bytes)
+    }
+
+    /// Saves as PPM or PGM depending on the extension of `path`
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let path = path.as_ref();
+        let write = match path.extension().and_then(|e| e.to_str()) {
+            Some("ppm") => Self::write_ppm,
+            Some("pgm") => Self::write_pgm,
+            _ => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("{} isn't a .ppm or .pgm file", path.display()),
+                ))
+            }
+        };
+        let mut out = BufWriter::new(File::create(path)?);
+        write(self, &mut out)?;
+        out.flush()
+    }
+}
+
+impl Index<Point> for Image {
+    type Output = Rgb;
+
+    fn index(&self, p: Point) -> &Self::Output {
+        assert!(self.contains(p), "{:?} is outside the image", p);
+        &self.pixels[p.y() as usize * self.width + p.x() as usize]
+    }
+}
+
+impl IndexMut<Point> for Image {
+    fn index_mut(&mut self, p: Point) -> &mut Self::Output {
+        assert!(self.contains(p), "{:?} is outside the image", p);
+        &mut self.pixels[p.y() as usize * self.width + p.x() as usize]
+    }
+}
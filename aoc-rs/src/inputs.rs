@@ -0,0 +1,82 @@
+//! Locating puzzle inputs. Files are read from `../input` when they exist, otherwise from the
+//! copies compiled into the binary by the `embed-inputs` feature.
+
+use std::path::{Path, PathBuf};
+
+use crate::lines_from_file;
+
+#[cfg(feature = "embed-inputs")]
+include!(concat!(env!("OUT_DIR"), "/inputs.rs"));
+
+#[cfg(not(feature = "embed-inputs"))]
+static EMBEDDED: &[(&str, &str)] = &[];
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum InputKind {
+    Example,
+    Problem,
+}
+
+impl InputKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            InputKind::Example => "example",
+            InputKind::Problem => "problem",
+        }
+    }
+}
+
+/// Where an input would be loaded from
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Source {
+    Disk,
+    Embedded,
+    Missing,
+}
+
+impl std::fmt::Display for Source {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.pad(match self {
+            Source::Disk => "yes",
+            Source::Embedded => "embedded",
+            Source::Missing => "no",
+        })
+    }
+}
+
+pub fn filename(problem: u8, kind: InputKind) -> String {
+    match kind {
+        InputKind::Example => format!("problem.{}.example", problem),
+        InputKind::Problem => format!("problem.{}.input", problem),
+    }
+}
+
+pub fn path(problem: u8, kind: InputKind) -> PathBuf {
+    Path::new("../input").join(filename(problem, kind))
+}
+
+pub fn embedded(problem: u8, kind: InputKind) -> Option<&'static str> {
+    let name = filename(problem, kind);
+    EMBEDDED
+        .iter()
+        .find(|(n, _)| *n == name)
+        .map(|(_, contents)| *contents)
+}
+
+pub fn source(problem: u8, kind: InputKind) -> Source {
+    if path(problem, kind).exists() {
+        Source::Disk
+    } else if embedded(problem, kind).is_some() {
+        Source::Embedded
+    } else {
+        Source::Missing
+    }
+}
+
+pub fn load(problem: u8, kind: InputKind) -> Option<Vec<String>> {
+    match source(problem, kind) {
+        Source::Disk => Some(lines_from_file(path(problem, kind))),
+        Source::Embedded => embedded(problem, kind).map(|s| s.lines().map(String::from).collect()),
+        Source::Missing => None,
+    }
+}
@@ -0,0 +1,159 @@
+use std::ops::RangeInclusive;
+
+use crate::point::Coordinate;
+
+/// A set of integers stored as sorted, disjoint inclusive ranges. Ranges that overlap or touch
+/// are merged as they're inserted, so every operation costs in proportion to the number of
+/// ranges rather than the number of integers covered.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct IntervalSet<T> {
+    ranges: Vec<(T, T)>,
+}
+
+impl<T: Coordinate> IntervalSet<T> {
+    pub fn new() -> Self {
+        Self { ranges: vec![] }
+    }
+
+    /// Adds every integer in `range`, an empty range changes nothing
+    pub fn insert(&mut self, range: RangeInclusive<T>) {
+        let (mut start, mut end) = range.into_inner();
+        if start > end {
+            return;
+        }
+
+        // the ranges that overlap or touch the new one all become part of it
+        let first = self
+            .ranges
+            .partition_point(|&(_, e)| e.checked_add(T::ONE).is_some_and(|e| e < start));
+        let last = self
+            .ranges
+            .partition_point(|&(s, _)| s.checked_sub(T::ONE).is_none_or(|s| s <= end));
+        if first < last {
+            start = start.min(self.ranges[first].0);
+            end = end.max(self.ranges[last - 1].1);
+        }
+        self.ranges.splice(first..last, [(start, end)]);
+    }
+
+    /// Takes out every integer in `range`, splitting the ranges it cuts through
+    pub fn remove(&mut self, range: RangeInclusive<T>) {
+        let (start, end) = range.into_inner();
+        if start > end {
+            return;
+        }
+
+        let first = self.ranges.partition_point(|&(_, e)| e < start);
+        let last = self.ranges.partition_point(|&(s, _)| s <= end);
+        if first == last {
+            return;
+        }
+
+        let mut kept = vec![];
+        let (s, _) = self.ranges[first];
+        if s < start {
+            kept.push((s, start - T::ONE));
+        }
+        let (_, e) = self.ranges[last - 1];
+        if e > end {
+            kept.push((end + T::ONE, e));
+        }
+        self.ranges.splice(first..last, kept);
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        let mut union = self.clone();
+        for range in other.ranges() {
+            union.insert(range);
+        }
+        union
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut intersection = Self::new();
+        let (mut i, mut j) = (0, 0);
+        while i < self.ranges.len() && j < other.ranges.len() {
+            let (a, b) = (self.ranges[i], other.ranges[j]);
+            let (start, end) = (a.0.max(b.0), a.1.min(b.1));
+            if start <= end {
+                intersection.ranges.push((start, end));
+            }
+
+            // whichever ends first can't overlap anything else
+            match a.1 < b.1 {
+                true => i += 1,
+                false => j += 1,
+            }
+        }
+        intersection
+    }
+
+    /// The integers in `self` but not in `other`
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut difference = self.clone();
+        for range in other.ranges() {
+            difference.remove(range);
+        }
+        difference
+    }
+
+    /// The integers within `bounds` that aren't in the set
+    pub fn gaps(&self, bounds: RangeInclusive<T>) -> Self {
+        Self::from(bounds).difference(self)
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        let i = self.ranges.partition_point(|&(_, e)| e < value);
+        self.ranges.get(i).is_some_and(|&(s, _)| s <= value)
+    }
+
+    /// Whether every integer in `range` is in the set, which is always true of an empty range
+    pub fn contains_range(&self, range: &RangeInclusive<T>) -> bool {
+        if range.is_empty() {
+            return true;
+        }
+
+        let i = self.ranges.partition_point(|&(_, e)| e < *range.start());
+        self.ranges
+            .get(i)
+            .is_some_and(|&(s, e)| s <= *range.start() && *range.end() <= e)
+    }
+
+    pub fn is_superset(&self, other: &Self) -> bool {
+        other.ranges().all(|r| self.contains_range(&r))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    /// How many integers the set holds
+    pub fn total_len(&self) -> T {
+        self.ranges
+            .iter()
+            .fold(T::ZERO, |total, &(s, e)| total + (e - s) + T::ONE)
+    }
+
+    /// The disjoint ranges making up the set, in order
+    pub fn ranges(&self) -> impl DoubleEndedIterator<Item = RangeInclusive<T>> + '_ {
+        self.ranges.iter().map(|&(s, e)| s..=e)
+    }
+}
+
+impl<T: Coordinate> From<RangeInclusive<T>> for IntervalSet<T> {
+    fn from(range: RangeInclusive<T>) -> Self {
+        let mut set = Self::new();
+        set.insert(range);
+        set
+    }
+}
+
+impl<T: Coordinate> FromIterator<RangeInclusive<T>> for IntervalSet<T> {
+    fn from_iter<I: IntoIterator<Item = RangeInclusive<T>>>(iter: I) -> Self {
+        let mut set = Self::new();
+        for range in iter {
+            set.insert(range);
+        }
+        set
+    }
+}
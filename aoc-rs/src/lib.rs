@@ -1,3 +1,15 @@
+pub mod arena;
+pub mod bitset;
+pub mod counters;
+pub mod cycles;
+pub mod distances;
+pub mod grid;
+pub mod image;
+pub mod inputs;
+pub mod intervals;
+pub mod math;
+pub mod optimize;
+pub mod parse;
 pub mod point;
 pub mod problem1;
 pub mod problem10;
@@ -18,13 +30,21 @@ pub mod problem6;
 pub mod problem7;
 pub mod problem8;
 pub mod problem9;
+pub mod recording;
+pub mod search;
+pub mod terminal;
+pub mod top;
+pub mod voxels;
+pub mod window;
 
+use image::Image;
 use num_enum::TryFromPrimitive;
 use std::{
     fs::File,
     io::{prelude::*, BufReader},
     path::Path,
 };
+use terminal::Canvas;
 
 #[derive(TryFromPrimitive, Copy, Clone)]
 #[repr(u8)]
@@ -51,10 +71,160 @@ pub enum Problems {
     Problem19,
 }
 
+impl Problems {
+    /// Every implemented day, in order
+    pub fn all() -> impl Iterator<Item = Problems> {
+        (1..).map_while(|n| Problems::try_from(n).ok())
+    }
+
+    pub fn solver(self) -> Option<Box<dyn Problem>> {
+        let problem: Box<dyn Problem> = match self {
+            Problems::Invalid => return None,
+            Problems::Problem1 => Box::new(problem1::Problem1 {}),
+            Problems::Problem2 => Box::new(problem2::Problem2 {}),
+            Problems::Problem3 => Box::new(problem3::Problem3 {}),
+            Problems::Problem4 => Box::new(problem4::Problem4 {}),
+            Problems::Problem5 => Box::new(problem5::Problem5 {}),
+            Problems::Problem6 => Box::new(problem6::Problem6 {}),
+            Problems::Problem7 => Box::new(problem7::Problem7 {}),
+            Problems::Problem8 => Box::new(problem8::Problem8 {}),
+            Problems::Problem9 => Box::new(problem9::Problem9 {}),
+            Problems::Problem10 => Box::new(problem10::Problem10 {}),
+            Problems::Problem11 => Box::new(problem11::Problem11 {}),
+            Problems::Problem12 => Box::new(problem12::Problem12 {}),
+            Problems::Problem13 => Box::new(problem13::Problem13 {}),
+            Problems::Problem14 => Box::new(problem14::Problem14 {}),
+            Problems::Problem15 => Box::new(problem15::Problem15 {}),
+            Problems::Problem16 => Box::new(problem16::Problem16 {}),
+            Problems::Problem17 => Box::new(problem17::Problem17 {}),
+            Problems::Problem18 => Box::new(problem18::Problem18 {}),
+            Problems::Problem19 => Box::new(problem19::Problem19 {}),
+        };
+        Some(problem)
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Part {
+    One,
+    Two,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Status {
+    Solved,
+    Unsolved,
+}
+
+impl std::fmt::Display for Status {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.pad(match self {
+            Status::Solved => "solved",
+            Status::Unsolved => "unsolved",
+        })
+    }
+}
+
+/// Loose categories used to find days that exercise a particular technique
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Tag {
+    Parsing,
+    Simulation,
+    Grid,
+    Graph,
+    Search,
+    Math,
+}
+
+impl std::fmt::Display for Tag {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.pad(match self {
+            Tag::Parsing => "parsing",
+            Tag::Simulation => "simulation",
+            Tag::Grid => "grid",
+            Tag::Graph => "graph",
+            Tag::Search => "search",
+            Tag::Math => "math",
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Metadata {
+    pub title: &'static str,
+    pub part1: Status,
+    pub part2: Status,
+    pub tags: &'static [Tag],
+}
+
+impl Metadata {
+    pub fn status(&self, part: Part) -> Status {
+        match part {
+            Part::One => self.part1,
+            Part::Two => self.part2,
+        }
+    }
+}
+
+/// A named alternative solver for one part of a problem, e.g. a naive approach kept around to
+/// check an optimized one against
+#[derive(Debug, Copy, Clone)]
+pub struct Variant {
+    pub name: &'static str,
+    pub solve: fn(&[String]) -> String,
+}
+
+/// Evidence backing up an answer, as an ordered list of labeled facts
+#[derive(Debug, Clone, Default)]
+pub struct Explanation {
+    entries: Vec<(String, String)>,
+}
+
+impl Explanation {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add<L: Into<String>, V: ToString>(&mut self, label: L, value: V) {
+        self.entries.push((label.into(), value.to_string()));
+    }
+
+    pub fn with<L: Into<String>, V: ToString>(mut self, label: L, value: V) -> Self {
+        self.add(label, value);
+        self
+    }
+
+    pub fn entries(&self) -> &[(String, String)] {
+        &self.entries
+    }
+}
+
 pub trait Problem {
+    fn metadata(&self) -> Metadata;
+
     fn solve_part1(&mut self, lines: &[String]) -> String;
 
     fn solve_part2(&mut self, lines: &[String]) -> String;
+
+    /// Additional solvers for `part`, `solve_part1`/`solve_part2` are always the "default" variant
+    fn variants(&self, _part: Part) -> Vec<Variant> {
+        vec![]
+    }
+
+    /// Why the default solver for `part` gives the answer it does, if the problem can say
+    fn explain(&mut self, _part: Part, _lines: &[String]) -> Option<Explanation> {
+        None
+    }
+
+    /// A picture of the puzzle as solved for `part`, if the problem can draw one
+    fn render(&mut self, _part: Part, _lines: &[String]) -> Option<Image> {
+        None
+    }
+
+    /// The puzzle as solved for `part` drawn in text, with the cells that matter highlighted
+    fn show(&mut self, _part: Part, _lines: &[String]) -> Option<Canvas> {
+        None
+    }
 }
 
 // helper functions
@@ -66,8 +236,31 @@ pub fn lines_from_file(filename: impl AsRef<Path>) -> Vec<String> {
         .collect()
 }
 
-pub fn transpose<T: Clone>(v: Vec<Vec<T>>) -> Vec<Vec<T>> {
-    (0..v[0].len())
-        .map(|i| v.iter().map(|inner| inner[i].clone()).collect())
+/// `s` as a quoted JSON string
+pub fn json_string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// Rows become columns. Short rows are padded with `fill`, so every column is as long as there
+/// are rows.
+pub fn transpose<T: Clone>(v: Vec<Vec<T>>, fill: T) -> Vec<Vec<T>> {
+    let grid = grid::Grid::from_ragged(v, fill);
+    grid.view(grid::Transform::Transpose)
+        .rows()
+        .map(|row| row.cloned().collect())
         .collect()
 }
@@ -1,65 +1,487 @@
-use clap::Parser;
+use std::{
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
 
-use aoc::*;
+use clap::{CommandFactory, ErrorKind, Parser, Subcommand};
+use itertools::Itertools;
 
-/// Simple program to greet a person
+use aoc::{inputs::InputKind, recording::Recording, *};
+
+/// Runs the Advent of Code 2022 solutions
 #[derive(Parser, Debug)]
-#[clap(author, version, about, long_about = None)]
+#[clap(author, version, about, long_about = None, subcommand_negates_reqs = true)]
 struct Args {
-    #[clap(short, long, value_parser)]
-    problem: u8,
+    #[clap(short, long, value_parser, required = true)]
+    problem: Option<u8>,
 
     #[clap(short, long, value_parser, default_value_t = false)]
     example: bool,
 
     #[clap(long, value_parser, default_value_t = false)]
     part2: bool,
+
+    /// Solve with the named variant instead of the default solver
+    #[clap(long, value_parser)]
+    variant: Option<String>,
+
+    /// Run every variant, reporting disagreements and timings
+    #[clap(
+        long,
+        value_parser,
+        default_value_t = false,
+        conflicts_with = "variant"
+    )]
+    compare: bool,
+
+    /// Print the results as a single JSON object
+    #[clap(long, value_parser, default_value_t = false)]
+    json: bool,
+
+    /// Show the evidence behind each answer, for problems that provide it
+    #[clap(
+        long,
+        value_parser,
+        default_value_t = false,
+        conflicts_with = "compare"
+    )]
+    explain: bool,
+
+    /// Print the solved puzzle with the cells that matter highlighted, for problems that can
+    #[clap(
+        long,
+        value_parser,
+        default_value_t = false,
+        conflicts_with_all = &["compare", "json"]
+    )]
+    show: bool,
+
+    /// The most columns of a puzzle `--show` prints, bigger ones are cropped
+    #[clap(long, value_parser, default_value_t = 120)]
+    view_width: usize,
+
+    /// The most rows of a puzzle `--show` prints
+    #[clap(long, value_parser, default_value_t = 40)]
+    view_height: usize,
+
+    /// Save a picture of each solved puzzle in this directory, for problems that can draw one
+    #[clap(long, value_parser, conflicts_with = "compare")]
+    image: Option<PathBuf>,
+
+    /// How many pixels wide each cell of a saved picture is
+    #[clap(long, value_parser = clap::value_parser!(u64).range(1..), default_value_t = 1)]
+    scale: u64,
+
+    /// Save a recording of each simulation in this directory, for problems that animate
+    #[clap(long, value_parser, conflicts_with = "compare")]
+    record: Option<PathBuf>,
+
+    /// Also save every recorded frame as a numbered text file
+    #[clap(long, value_parser, default_value_t = false, requires = "record")]
+    frames: bool,
+
+    /// Playback speed of saved recordings
+    #[clap(long, value_parser, default_value_t = 10)]
+    fps: u32,
+
+    /// Stop recording after this many frames
+    #[clap(long, value_parser, default_value_t = 1000)]
+    max_frames: usize,
+
+    #[clap(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Show every day with its status and whether its input files exist
+    List,
+}
+
+fn list() {
+    println!(
+        "{:>3}  {:<24}  {:<8}  {:<8}  {:<8}  {:<8}  tags",
+        "day", "title", "part 1", "part 2", "example", "input"
+    );
+    for problem_number in Problems::all() {
+        let n = problem_number as u8;
+        let metadata = problem_number.solver().unwrap().metadata();
+        println!(
+            "{:>3}  {:<24}  {:<8}  {:<8}  {:<8}  {:<8}  {}",
+            n,
+            metadata.title,
+            metadata.part1,
+            metadata.part2,
+            inputs::source(n, InputKind::Example),
+            inputs::source(n, InputKind::Problem),
+            metadata.tags.iter().join(", ")
+        );
+    }
+}
+
+fn solve(problem: &mut dyn Problem, part: Part, variant: &str, lines: &[String]) -> String {
+    if problem.metadata().status(part) == Status::Unsolved {
+        return "not implemented".to_string();
+    }
+
+    match (variant, part) {
+        ("default", Part::One) => problem.solve_part1(lines),
+        ("default", Part::Two) => problem.solve_part2(lines),
+        (name, _) => {
+            let variant = problem.variants(part).into_iter().find(|v| v.name == name);
+            (variant
+                .expect("variant names are checked before solving")
+                .solve)(lines)
+        }
+    }
+}
+
+fn variant_names(problem: &dyn Problem, part: Part) -> Vec<&'static str> {
+    std::iter::once("default")
+        .chain(problem.variants(part).into_iter().map(|v| v.name))
+        .collect()
+}
+
+/// Exits with a usage error unless every part in `parts` has a variant named `name`
+fn check_variant(problem: &dyn Problem, parts: &[Part], name: &str) {
+    for &part in parts {
+        let names = variant_names(problem, part);
+        if !names.contains(&name) {
+            let number = match part {
+                Part::One => 1,
+                Part::Two => 2,
+            };
+            Args::command()
+                .error(
+                    ErrorKind::InvalidValue,
+                    format!(
+                        "no variant named {} for part {} (available: {})",
+                        name,
+                        number,
+                        names.join(", ")
+                    ),
+                )
+                .exit();
+        }
+    }
+}
+
+/// Everything learned from solving one part of one input with one variant
+struct Outcome {
+    part: Part,
+    input: &'static str,
+    variant: String,
+    answer: String,
+    elapsed: Duration,
+    counters: Vec<(&'static str, u64)>,
+    explanation: Option<Explanation>,
+}
+
+impl Outcome {
+    fn print_counters(&self) {
+        if !self.counters.is_empty() {
+            println!(
+                "    counters: {}",
+                self.counters
+                    .iter()
+                    .map(|(name, n)| format!("{}={}", name, n))
+                    .join(", ")
+            );
+        }
+    }
+
+    fn to_json(&self) -> String {
+        let counters = self
+            .counters
+            .iter()
+            .map(|(name, n)| format!("{}:{}", json_string(name), n))
+            .join(",");
+        let explanation = match &self.explanation {
+            Some(e) => format!(
+                "[{}]",
+                e.entries()
+                    .iter()
+                    .map(|(label, value)| format!(
+                        "{{\"label\":{},\"value\":{}}}",
+                        json_string(label),
+                        json_string(value)
+                    ))
+                    .join(",")
+            ),
+            None => "null".to_string(),
+        };
+        format!(
+            "{{\"part\":{},\"input\":{},\"variant\":{},\"answer\":{},\"elapsed_us\":{},\"counters\":{{{}}},\"explanation\":{}}}",
+            match self.part {
+                Part::One => 1,
+                Part::Two => 2,
+            },
+            json_string(self.input),
+            json_string(&self.variant),
+            json_string(&self.answer),
+            self.elapsed.as_micros(),
+            counters,
+            explanation
+        )
+    }
+}
+
+/// Solves `lines` with `variant`, timing it and collecting the counters it bumped
+fn measure(
+    problem: &mut dyn Problem,
+    part: Part,
+    variant: &str,
+    input: &'static str,
+    lines: &[String],
+) -> Outcome {
+    counters::reset();
+    let start = Instant::now();
+    let answer = solve(problem, part, variant, lines);
+    let elapsed = start.elapsed();
+
+    Outcome {
+        part,
+        input,
+        variant: variant.to_string(),
+        answer,
+        elapsed,
+        counters: counters::take(),
+        explanation: None,
+    }
+}
+
+/// Solves `lines` with every variant, flagging answers that differ from the default's
+fn compare(
+    problem: &mut dyn Problem,
+    part: Part,
+    args: &Args,
+    input: &'static str,
+    lines: &[String],
+) -> Vec<Outcome> {
+    let outcomes: Vec<_> = variant_names(problem, part)
+        .into_iter()
+        .map(|name| measure(problem, part, name, input, lines))
+        .collect();
+
+    if !args.json {
+        println!("{}:", input);
+        let expected = &outcomes[0];
+        for outcome in outcomes.iter() {
+            let verdict = match outcome.answer == expected.answer {
+                true => "",
+                false => "  <- disagrees with default",
+            };
+            // the default can finish too fast for the clock to see
+            let ratio = match expected.elapsed.is_zero() {
+                true => "n/a".to_string(),
+                false => format!(
+                    "{:.2}x",
+                    outcome.elapsed.as_secs_f64() / expected.elapsed.as_secs_f64()
+                ),
+            };
+            println!(
+                "  {:<16} {:>20} {:>12.3?} ({}){}",
+                outcome.variant, outcome.answer, outcome.elapsed, ratio, verdict
+            );
+            outcome.print_counters();
+        }
+    }
+
+    outcomes
+}
+
+fn run(
+    problem: &mut dyn Problem,
+    part: Part,
+    args: &Args,
+    input: &'static str,
+    lines: &[String],
+) -> Vec<Outcome> {
+    if args.compare {
+        return compare(problem, part, args, input, lines);
+    }
+
+    let variant = args.variant.as_deref().unwrap_or("default");
+    let mut outcome = measure(problem, part, variant, input, lines);
+    if args.explain && problem.metadata().status(part) == Status::Solved {
+        outcome.explanation = problem.explain(part, lines);
+    }
+
+    if !args.json {
+        println!("{}: {}", input, outcome.answer);
+        outcome.print_counters();
+        if args.explain {
+            match &outcome.explanation {
+                Some(explanation) => {
+                    for (label, value) in explanation.entries() {
+                        println!("    {}: {}", label, value);
+                    }
+                }
+                None => println!("    (no explanation available)"),
+            }
+        }
+        if args.show && problem.metadata().status(part) == Status::Solved {
+            match problem.show(part, lines) {
+                Some(canvas) => canvas.viewport(args.view_width, args.view_height).print(),
+                None => println!("    (no picture available)"),
+            }
+        }
+    }
+
+    if let Some(directory) = &args.image {
+        if problem.metadata().status(part) == Status::Solved {
+            save_image(problem, part, args, directory, input, lines);
+        }
+    }
+
+    if let Some(directory) = &args.record {
+        if problem.metadata().status(part) == Status::Solved {
+            save_recording(problem, part, variant, args, directory, input, lines);
+        }
+    }
+
+    vec![outcome]
+}
+
+/// What saved pictures and recordings are named after, e.g. `day12-part1-example`
+fn output_name(args: &Args, part: Part, input: &str) -> String {
+    let part = match part {
+        Part::One => 1,
+        Part::Two => 2,
+    };
+    format!("day{}-part{}-{}", args.problem.unwrap(), part, input)
+}
+
+/// Renders the puzzle and saves it as e.g. `day12-part1-example.pgm`
+fn save_image(
+    problem: &mut dyn Problem,
+    part: Part,
+    args: &Args,
+    directory: &Path,
+    input: &'static str,
+    lines: &[String],
+) {
+    let Some(image) = problem.render(part, lines) else {
+        if !args.json {
+            println!("    (no image available)");
+        }
+        return;
+    };
+
+    let image = image.scaled(args.scale as usize);
+    let path = directory.join(format!(
+        "{}.{}",
+        output_name(args, part, input),
+        image.extension()
+    ));
+    match image.save(&path) {
+        Ok(()) if !args.json => println!("    image: {}", path.display()),
+        Ok(()) => {}
+        Err(e) => eprintln!("couldn't save {}: {}", path.display(), e),
+    }
+}
+
+/// Solves again while recording, saves the recording as e.g. `day9-part1-example.cast` and, with
+/// `--frames`, the frames in a directory named the same
+fn save_recording(
+    problem: &mut dyn Problem,
+    part: Part,
+    variant: &str,
+    args: &Args,
+    directory: &Path,
+    input: &'static str,
+    lines: &[String],
+) {
+    recording::start(Recording::new(args.fps, args.max_frames));
+    solve(problem, part, variant, lines);
+    let recording = recording::finish().unwrap();
+    if recording.frames().is_empty() {
+        if !args.json {
+            println!("    (no recording available)");
+        }
+        return;
+    }
+
+    let name = output_name(args, part, input);
+    let path = directory.join(format!("{}.cast", name));
+    let saved = recording
+        .save_asciicast(&path)
+        .and_then(|()| match args.frames {
+            true => recording.save_frames(directory.join(&name)),
+            false => Ok(()),
+        });
+    match saved {
+        Ok(()) if !args.json => println!(
+            "    recording: {} ({} frames)",
+            path.display(),
+            recording.frames().len()
+        ),
+        Ok(()) => {}
+        Err(e) => eprintln!("couldn't save {}: {}", path.display(), e),
+    }
 }
 
 fn main() {
     let args = Args::parse();
-    let problem_number = Problems::try_from(args.problem).unwrap();
-
-    let example_filename = format!("../input/problem.{}.example", args.problem);
-    let input_filename = format!("../input/problem.{}.input", args.problem);
-
-    let example_lines = lines_from_file(example_filename);
-    let input_lines = lines_from_file(input_filename);
-
-    let mut problem: Box<dyn Problem> = match problem_number {
-        Problems::Invalid => panic!("Invalid problem number"),
-        Problems::Problem1 => Box::new(problem1::Problem1 {}),
-        Problems::Problem2 => Box::new(problem2::Problem2 {}),
-        Problems::Problem3 => Box::new(problem3::Problem3 {}),
-        Problems::Problem4 => Box::new(problem4::Problem4 {}),
-        Problems::Problem5 => Box::new(problem5::Problem5 {}),
-        Problems::Problem6 => Box::new(problem6::Problem6 {}),
-        Problems::Problem7 => Box::new(problem7::Problem7 {}),
-        Problems::Problem8 => Box::new(problem8::Problem8 {}),
-        Problems::Problem9 => Box::new(problem9::Problem9 {}),
-        Problems::Problem10 => Box::new(problem10::Problem10 {}),
-        Problems::Problem11 => Box::new(problem11::Problem11 {}),
-        Problems::Problem12 => Box::new(problem12::Problem12 {}),
-        Problems::Problem13 => Box::new(problem13::Problem13 {}),
-        Problems::Problem14 => Box::new(problem14::Problem14 {}),
-        Problems::Problem15 => Box::new(problem15::Problem15 {}),
-        Problems::Problem16 => Box::new(problem16::Problem16 {}),
-        Problems::Problem17 => Box::new(problem17::Problem17 {}),
-        Problems::Problem18 => Box::new(problem18::Problem18 {}),
-        Problems::Problem19 => Box::new(problem19::Problem19 {}),
-    };
+    if let Some(Command::List) = args.command {
+        list();
+        return;
+    }
+
+    let problem = args.problem.unwrap();
+    let mut problem_solver = Problems::try_from(problem)
+        .ok()
+        .and_then(Problems::solver)
+        .expect("Invalid problem number");
+
+    let inputs: Vec<_> = [InputKind::Example, InputKind::Problem]
+        .into_iter()
+        .filter(|&kind| kind == InputKind::Example || !args.example)
+        .map(|kind| (kind, inputs::load(problem, kind)))
+        .collect();
 
+    let mut outcomes = vec![];
+    let mut parts = vec![];
     if !args.part2 {
-        println!("part 1");
-        println!("example: {}", problem.solve_part1(&example_lines));
-        if !args.example {
-            println!("problem: {}", problem.solve_part1(&input_lines));
+        parts.push(Part::One);
+    }
+    parts.push(Part::Two);
+    if let Some(name) = &args.variant {
+        check_variant(problem_solver.as_ref(), &parts, name);
+    }
+
+    for part in parts {
+        if !args.json {
+            match part {
+                Part::One => println!("part 1"),
+                Part::Two => println!("\npart 2"),
+            }
+        }
+
+        for (kind, lines) in inputs.iter() {
+            match lines {
+                Some(lines) => outcomes.extend(run(
+                    problem_solver.as_mut(),
+                    part,
+                    &args,
+                    kind.label(),
+                    lines,
+                )),
+                None if !args.json => println!(
+                    "{}: no input at {}",
+                    kind.label(),
+                    inputs::path(problem, *kind).display()
+                ),
+                None => {}
+            }
         }
     }
 
-    println!("\npart 2");
-    println!("example: {}", problem.solve_part2(&example_lines));
-    if !args.example {
-        println!("problem: {}", problem.solve_part2(&input_lines));
+    if args.json {
+        println!(
+            "{{\"problem\":{},\"results\":[{}]}}",
+            problem,
+            outcomes.iter().map(Outcome::to_json).join(",")
+        );
     }
 }
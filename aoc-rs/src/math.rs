@@ -0,0 +1,95 @@
+//! Number theory on `i64`s: divisors, arithmetic modulo a number, and the Chinese Remainder
+//! Theorem. Results are always in `0..m` for a modulus `m`, negative inputs included, and
+//! intermediate products are done in `i128` so nothing overflows on the way.
+
+/// The greatest common divisor. `gcd(0, 0)` is 0. Unsigned, since `gcd(i64::MIN, 0)` is 2^63,
+/// which doesn't fit in an `i64`.
+pub fn gcd(a: i64, b: i64) -> u64 {
+    let (mut a, mut b) = (a.unsigned_abs(), b.unsigned_abs());
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+/// The least common multiple, never negative, `None` if it doesn't fit in an `i64`
+pub fn lcm(a: i64, b: i64) -> Option<i64> {
+    if a == 0 || b == 0 {
+        return Some(0);
+    }
+    let lcm = (a as i128 / gcd(a, b) as i128 * b as i128).abs();
+    i64::try_from(lcm).ok()
+}
+
+/// The least common multiple of all of `values`, 1 when there are none
+pub fn lcm_all(values: impl IntoIterator<Item = i64>) -> Option<i64> {
+    values.into_iter().try_fold(1, lcm)
+}
+
+/// `(g, x, y)` where `g = gcd(a, b) = a * x + b * y`
+pub fn extended_gcd(a: i64, b: i64) -> (i64, i64, i64) {
+    let (mut r0, mut r1) = (a as i128, b as i128);
+    let (mut x0, mut x1) = (1i128, 0i128);
+    let (mut y0, mut y1) = (0i128, 1i128);
+    while r1 != 0 {
+        let q = r0 / r1;
+        (r0, r1) = (r1, r0 - q * r1);
+        (x0, x1) = (x1, x0 - q * x1);
+        (y0, y1) = (y1, y0 - q * y1);
+    }
+    if r0 < 0 {
+        (r0, x0, y0) = (-r0, -x0, -y0);
+    }
+    (r0 as i64, x0 as i64, y0 as i64)
+}
+
+/// `a + b` modulo `m`, which must be positive
+pub fn add_mod(a: i64, b: i64, m: i64) -> i64 {
+    ((a as i128 + b as i128).rem_euclid(m as i128)) as i64
+}
+
+/// `a * b` modulo `m`, which must be positive
+pub fn mul_mod(a: i64, b: i64, m: i64) -> i64 {
+    ((a as i128 * b as i128).rem_euclid(m as i128)) as i64
+}
+
+/// `base` to the power `exp` modulo `m`, by repeated squaring
+pub fn pow_mod(base: i64, mut exp: u64, m: i64) -> i64 {
+    let mut base = base.rem_euclid(m);
+    let mut result = 1 % m;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = mul_mod(result, base, m);
+        }
+        base = mul_mod(base, base, m);
+        exp >>= 1;
+    }
+    result
+}
+
+/// The `x` in `0..m` with `a * x = 1` modulo `m`, `None` unless `a` and `m` are coprime
+pub fn mod_inverse(a: i64, m: i64) -> Option<i64> {
+    let (g, x, _) = extended_gcd(a.rem_euclid(m), m);
+    (g == 1).then(|| x.rem_euclid(m))
+}
+
+/// Solves `x = r` modulo `m` for every `(r, m)` at once, the moduli being positive. Outputs the
+/// smallest non-negative `x` with the LCM of the moduli, every other solution being `x` plus a
+/// multiple of it. The moduli needn't be coprime; `None` when the congruences contradict each
+/// other or the LCM doesn't fit in an `i64`.
+pub fn crt(congruences: impl IntoIterator<Item = (i64, i64)>) -> Option<(i64, i64)> {
+    congruences.into_iter().try_fold((0, 1), |(x, m), (r, n)| {
+        // x + m * k = r (mod n), i.e. m * k = r - x (mod n)
+        let (g, inverse, _) = extended_gcd(m, n);
+        let difference = r as i128 - x as i128;
+        if difference % g as i128 != 0 {
+            return None;
+        }
+
+        let step = n / g;
+        let k = (difference / g as i128 % step as i128 * inverse as i128).rem_euclid(step as i128);
+        let modulus = lcm(m, n)?;
+        let x = (x as i128 + m as i128 * k).rem_euclid(modulus as i128);
+        Some((x as i64, modulus))
+    })
+}
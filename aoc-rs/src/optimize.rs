@@ -0,0 +1,126 @@
+//! Depth-first branch and bound, for puzzles that ask for the most of something that can be
+//! gained within a budget. Every state is a valid place to stop; moving to a successor gains
+//! some value, and a state's upper bound is the most that could still be gained from it.
+//! Branches whose bound can't beat the best found so far are never explored.
+
+use std::{collections::HashMap, hash::Hash, ops::Add};
+
+use crate::counters;
+
+/// The best value found and the states leading to it, from the start state included
+#[derive(Debug, Clone)]
+pub struct Optimum<S, V> {
+    pub value: V,
+    pub path: Vec<S>,
+}
+
+struct Explorer<'f, S, V, K> {
+    successors: &'f mut dyn FnMut(&S) -> Vec<(S, V)>,
+    upper_bound: &'f mut dyn FnMut(&S) -> V,
+    key: &'f mut dyn FnMut(&S) -> Option<K>,
+    /// The most value any path reaching a key has had so far
+    memo: HashMap<K, V>,
+    path: Vec<S>,
+    best: Optimum<S, V>,
+}
+
+impl<S, V, K> Explorer<'_, S, V, K>
+where
+    S: Clone,
+    V: Copy + Ord + Add<Output = V>,
+    K: Hash + Eq,
+{
+    /// Explores from the last state on the path, which was reached with `value`
+    fn explore(&mut self, value: V) {
+        counters::increment("states expanded");
+        let state = self.path.last().unwrap();
+        if value > self.best.value {
+            self.best = Optimum {
+                value,
+                path: self.path.clone(),
+            };
+        }
+
+        // whatever follows a state is the same however it was reached, so arriving with less
+        // value than before can't lead anywhere better
+        if let Some(key) = (self.key)(state) {
+            match self.memo.get(&key) {
+                Some(&seen) if seen >= value => return,
+                _ => {
+                    self.memo.insert(key, value);
+                }
+            }
+        }
+
+        if value + (self.upper_bound)(state) <= self.best.value {
+            return;
+        }
+
+        for (next, gain) in (self.successors)(state) {
+            self.path.push(next);
+            self.explore(value + gain);
+            self.path.pop();
+        }
+    }
+}
+
+fn run<S, V, K, I>(
+    start: S,
+    mut successors: impl FnMut(&S) -> I,
+    mut upper_bound: impl FnMut(&S) -> V,
+    mut key: impl FnMut(&S) -> Option<K>,
+) -> Optimum<S, V>
+where
+    S: Clone,
+    V: Copy + Ord + Add<Output = V> + Default,
+    K: Hash + Eq,
+    I: IntoIterator<Item = (S, V)>,
+{
+    let mut explorer = Explorer {
+        successors: &mut |s| successors(s).into_iter().collect(),
+        upper_bound: &mut upper_bound,
+        key: &mut key,
+        memo: HashMap::new(),
+        path: vec![start.clone()],
+        best: Optimum {
+            value: V::default(),
+            path: vec![start],
+        },
+    };
+    explorer.explore(V::default());
+    explorer.best
+}
+
+/// The most value that can be gained from `start`. `successors` outputs each state that can
+/// follow a state with the value gained by moving to it, and `upper_bound` must never be less
+/// than what can still be gained from a state.
+pub fn maximize<S, V, I>(
+    start: S,
+    successors: impl FnMut(&S) -> I,
+    upper_bound: impl FnMut(&S) -> V,
+) -> Optimum<S, V>
+where
+    S: Clone,
+    V: Copy + Ord + Add<Output = V> + Default,
+    I: IntoIterator<Item = (S, V)>,
+{
+    run(start, successors, upper_bound, |_| None::<()>)
+}
+
+/// Like `maximize`, but also remembers the best value each state was reached with, by `key`,
+/// and stops exploring a state reached again with no more value. States with the same key must
+/// have the same successors and bound.
+pub fn maximize_memoized<S, V, K, I>(
+    start: S,
+    successors: impl FnMut(&S) -> I,
+    upper_bound: impl FnMut(&S) -> V,
+    mut key: impl FnMut(&S) -> K,
+) -> Optimum<S, V>
+where
+    S: Clone,
+    V: Copy + Ord + Add<Output = V> + Default,
+    K: Hash + Eq,
+    I: IntoIterator<Item = (S, V)>,
+{
+    run(start, successors, upper_bound, |s| Some(key(s)))
+}
@@ -0,0 +1,273 @@
+//! A small parser-combinator library for the puzzle inputs.
+//!
+//! A parser is any function from the remaining input to either a value and what's left after it,
+//! or a `Failure` saying what was expected where. Parsers never hold on to the input, so they
+//! produce owned values, and any of them can be run over a whole input with `parse` or
+//! `parse_lines`, which turn a failure into a `ParseError` with its exact position.
+//!
+//! Alternatives and repetitions backtrack, so the failure that stops a parse is often not where
+//! the input is wrong. Instead, errors report the furthest any parser got into the input, and
+//! everything that was expected there.
+//!
+//! Bigger parsers, e.g. for a multi-line record, read best as a plain function running smaller
+//! ones in turn with `?`.
+
+use std::{cell::RefCell, fmt::Display, str::FromStr};
+
+thread_local! {
+    /// The length of the shortest rest any parser failed at, and what was expected there
+    static FURTHEST: RefCell<Option<(usize, Vec<String>)>> = const { RefCell::new(None) };
+}
+
+/// Where parsing stopped and what was expected there
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Failure<'a> {
+    rest: &'a str,
+    expected: String,
+}
+
+impl<'a> Failure<'a> {
+    pub fn new(rest: &'a str, expected: impl Into<String>) -> Self {
+        let failure = Self {
+            rest,
+            expected: expected.into(),
+        };
+        failure.record();
+        failure
+    }
+
+    fn record(&self) {
+        FURTHEST.with(|f| {
+            let mut furthest = f.borrow_mut();
+            match furthest.as_mut() {
+                Some((rest, expected)) if *rest == self.rest.len() => {
+                    if !expected.contains(&self.expected) {
+                        expected.push(self.expected.clone());
+                    }
+                }
+                Some((rest, _)) if *rest < self.rest.len() => {}
+                _ => *furthest = Some((self.rest.len(), vec![self.expected.clone()])),
+            }
+        });
+    }
+}
+
+pub type PResult<'a, T> = Result<(T, &'a str), Failure<'a>>;
+
+pub trait Parser<T>: for<'a> Fn(&'a str) -> PResult<'a, T> {}
+
+impl<T, F> Parser<T> for F where F: for<'a> Fn(&'a str) -> PResult<'a, T> {}
+
+/// Closures only get a signature generic over the input's lifetime when they're passed straight
+/// to a function asking for one
+fn parser<T, F>(f: F) -> F
+where
+    F: for<'a> Fn(&'a str) -> PResult<'a, T>,
+{
+    f
+}
+
+/// A failure to parse a whole input. `offset` is in bytes from the start of the input, `line`
+/// and `column` count from 1.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub offset: usize,
+    pub line: usize,
+    pub column: usize,
+    pub expected: String,
+}
+
+impl ParseError {
+    /// The error at the furthest failure recorded while parsing `text`
+    fn furthest(text: &str) -> Self {
+        let (rest, expected) = FURTHEST
+            .with(|f| f.borrow_mut().take())
+            .expect("a failure was recorded");
+        let offset = text.len() - rest;
+        let before = &text[..offset];
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+
+        Self {
+            offset,
+            line: before.matches('\n').count() + 1,
+            column: before[line_start..].chars().count() + 1,
+            expected: expected.join(" or "),
+        }
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "expected {} at line {}, column {} (offset {})",
+            self.expected, self.line, self.column, self.offset
+        )
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Runs `p` over all of `text`, anything left over is an error
+pub fn parse<T>(p: impl Parser<T>, text: &str) -> Result<T, ParseError> {
+    FURTHEST.with(|f| f.borrow_mut().take());
+    let result = match p(text) {
+        Ok((value, "")) => Ok(value),
+        Ok((_, rest)) => Err(Failure::new(rest, "end of input")),
+        Err(failure) => Err(failure),
+    };
+
+    match result {
+        Ok(value) => {
+            FURTHEST.with(|f| f.borrow_mut().take());
+            Ok(value)
+        }
+        Err(_) => Err(ParseError::furthest(text)),
+    }
+}
+
+/// Runs `p` over the lines joined back together, ignoring blank lines at the end
+pub fn parse_lines<T>(p: impl Parser<T>, lines: &[String]) -> Result<T, ParseError> {
+    let text = lines.join("\n");
+    parse(p, text.trim_end_matches('\n'))
+}
+
+pub fn literal(expected: &'static str) -> impl Parser<()> {
+    parser(move |input| match input.strip_prefix(expected) {
+        Some(rest) => Ok(((), rest)),
+        None => Err(Failure::new(input, format!("{:?}", expected))),
+    })
+}
+
+/// A single character matching `predicate`, `expected` describes it for errors
+pub fn satisfy(predicate: impl Fn(char) -> bool, expected: &'static str) -> impl Parser<char> {
+    parser(move |input| match input.chars().next() {
+        Some(c) if predicate(c) => Ok((c, &input[c.len_utf8()..])),
+        _ => Err(Failure::new(input, expected)),
+    })
+}
+
+/// One or more characters matching `predicate`
+pub fn take_while1(
+    predicate: impl Fn(char) -> bool,
+    expected: &'static str,
+) -> impl Parser<String> {
+    parser(move |input| {
+        let end = input
+            .char_indices()
+            .find(|&(_, c)| !predicate(c))
+            .map_or(input.len(), |(i, _)| i);
+        match end {
+            0 => Err(Failure::new(input, expected)),
+            _ => Ok((input[..end].to_string(), &input[end..])),
+        }
+    })
+}
+
+/// A run of letters and digits, e.g. a name or label
+pub fn word() -> impl Parser<String> {
+    take_while1(|c| c.is_ascii_alphanumeric(), "a word")
+}
+
+/// Zero or more spaces
+pub fn spaces() -> impl Parser<()> {
+    parser(|input| Ok(((), input.trim_start_matches(' '))))
+}
+
+/// Digits with an optional leading `-` or `+`. Numbers too big for `T` fail where they start.
+pub fn integer<T: FromStr>() -> impl Parser<T> {
+    parser(|input: &str| {
+        let unsigned = input.strip_prefix(['-', '+']).unwrap_or(input);
+        let digits = unsigned
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(unsigned.len());
+        if digits == 0 {
+            return Err(Failure::new(input, "an integer"));
+        }
+
+        let end = input.len() - unsigned.len() + digits;
+        match input[..end].parse() {
+            Ok(n) => Ok((n, &input[end..])),
+            Err(_) => Err(Failure::new(
+                input,
+                format!("an integer that fits in {}", std::any::type_name::<T>()),
+            )),
+        }
+    })
+}
+
+pub fn map<A, B>(p: impl Parser<A>, f: impl Fn(A) -> B) -> impl Parser<B> {
+    parser(move |input| p(input).map(|(a, rest)| (f(a), rest)))
+}
+
+/// `v` in place of whatever `p` parsed
+pub fn value<A, T: Clone>(p: impl Parser<A>, v: T) -> impl Parser<T> {
+    map(p, move |_| v.clone())
+}
+
+pub fn pair<A, B>(a: impl Parser<A>, b: impl Parser<B>) -> impl Parser<(A, B)> {
+    parser(move |input| {
+        let (a, rest) = a(input)?;
+        let (b, rest) = b(rest)?;
+        Ok(((a, b), rest))
+    })
+}
+
+/// Parses `first` then `p`, keeping `p`'s value
+pub fn preceded<A, T>(first: impl Parser<A>, p: impl Parser<T>) -> impl Parser<T> {
+    map(pair(first, p), |(_, t)| t)
+}
+
+/// Parses `p` then `last`, keeping `p`'s value
+pub fn terminated<T, B>(p: impl Parser<T>, last: impl Parser<B>) -> impl Parser<T> {
+    map(pair(p, last), |(t, _)| t)
+}
+
+pub fn delimited<A, T, B>(
+    first: impl Parser<A>,
+    p: impl Parser<T>,
+    last: impl Parser<B>,
+) -> impl Parser<T> {
+    preceded(first, terminated(p, last))
+}
+
+/// `a`, or `b` if `a` fails
+pub fn either<T>(a: impl Parser<T>, b: impl Parser<T>) -> impl Parser<T> {
+    parser(move |input| a(input).or_else(|_| b(input)))
+}
+
+pub fn opt<T>(p: impl Parser<T>) -> impl Parser<Option<T>> {
+    parser(move |input| match p(input) {
+        Ok((t, rest)) => Ok((Some(t), rest)),
+        Err(_) => Ok((None, input)),
+    })
+}
+
+/// One or more `p` with `separator` between them. A separator that isn't followed by another `p`
+/// is left unparsed.
+pub fn separated1<T, S>(p: impl Parser<T>, separator: impl Parser<S>) -> impl Parser<Vec<T>> {
+    parser(move |input| {
+        let (first, mut rest) = p(input)?;
+        let mut items = vec![first];
+        while let Ok((item, after)) = separator(rest).and_then(|(_, r)| p(r)) {
+            items.push(item);
+            rest = after;
+        }
+        Ok((items, rest))
+    })
+}
+
+/// Like `separated1`, but also accepts no `p` at all
+pub fn separated0<T, S>(p: impl Parser<T>, separator: impl Parser<S>) -> impl Parser<Vec<T>> {
+    map(opt(separated1(p, separator)), Option::unwrap_or_default)
+}
+
+/// One `p` per line
+pub fn lines<T>(p: impl Parser<T>) -> impl Parser<Vec<T>> {
+    separated1(p, literal("\n"))
+}
+
+/// One `p` per group of lines, with the groups separated by blank lines
+pub fn records<T>(p: impl Parser<T>) -> impl Parser<Vec<T>> {
+    separated1(p, literal("\n\n"))
+}
@@ -1,127 +1,509 @@
-#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
-pub struct Point {
-    x: i32,
-    y: i32,
+use std::{
+    fmt::Debug,
+    hash::Hash,
+    num::TryFromIntError,
+    ops::{Add, AddAssign, Index, Mul, Neg, Sub, SubAssign},
+    str::FromStr,
+};
+
+/// Signed integer types that can be used as point coordinates
+pub trait Coordinate:
+    Copy
+    + Debug
+    + Default
+    + Eq
+    + Ord
+    + Hash
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Neg<Output = Self>
+{
+    const ZERO: Self;
+    const ONE: Self;
+
+    fn abs(self) -> Self;
+    fn signum(self) -> Self;
+    fn checked_add(self, rhs: Self) -> Option<Self>;
+    fn checked_sub(self, rhs: Self) -> Option<Self>;
+    fn checked_mul(self, rhs: Self) -> Option<Self>;
+    fn checked_abs(self) -> Option<Self>;
 }
 
+macro_rules! coordinate {
+    ($($t:ty),*) => {
+        $(
+            impl Coordinate for $t {
+                const ZERO: Self = 0;
+                const ONE: Self = 1;
+
+                fn abs(self) -> Self {
+                    <$t>::abs(self)
+                }
+
+                fn signum(self) -> Self {
+                    <$t>::signum(self)
+                }
+
+                fn checked_add(self, rhs: Self) -> Option<Self> {
+                    <$t>::checked_add(self, rhs)
+                }
+
+                fn checked_sub(self, rhs: Self) -> Option<Self> {
+                    <$t>::checked_sub(self, rhs)
+                }
+
+                fn checked_mul(self, rhs: Self) -> Option<Self> {
+                    <$t>::checked_mul(self, rhs)
+                }
+
+                fn checked_abs(self) -> Option<Self> {
+                    <$t>::checked_abs(self)
+                }
+            }
+        )*
+    };
+}
+
+coordinate!(i8, i16, i32, i64, i128, isize);
+
+/// A point in `N` dimensions, `Point` and `Point3D` cover the usual puzzles
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
-pub struct Point3D {
-    x: i32,
-    y: i32,
-    z: i32,
+pub struct PointN<T, const N: usize> {
+    coords: [T; N],
+}
+
+pub type Point<T = i32> = PointN<T, 2>;
+pub type Point3D<T = i32> = PointN<T, 3>;
+pub type Point4D<T = i32> = PointN<T, 4>;
+
+impl<T: Coordinate, const N: usize> Default for PointN<T, N> {
+    fn default() -> Self {
+        Self::origin()
+    }
 }
 
-impl std::ops::Add for Point {
+impl<T: Coordinate, const N: usize> std::ops::Add for PointN<T, N> {
     type Output = Self;
 
     fn add(mut self, rhs: Self) -> Self::Output {
-        self.x += rhs.x;
-        self.y += rhs.y;
+        for (c, r) in self.coords.iter_mut().zip(rhs.coords) {
+            *c = *c + r;
+        }
         self
     }
 }
 
-impl std::ops::Sub for Point {
+impl<T: Coordinate, const N: usize> std::ops::Sub for PointN<T, N> {
     type Output = Self;
 
     fn sub(mut self, rhs: Self) -> Self::Output {
-        self.x -= rhs.x;
-        self.y -= rhs.y;
+        for (c, r) in self.coords.iter_mut().zip(rhs.coords) {
+            *c = *c - r;
+        }
         self
     }
 }
 
-impl std::ops::Add for Point3D {
+impl<T: Coordinate, const N: usize> AddAssign for PointN<T, N> {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl<T: Coordinate, const N: usize> SubAssign for PointN<T, N> {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+/// Scales every coordinate, e.g. a unit direction by a number of steps
+impl<T: Coordinate, const N: usize> Mul<T> for PointN<T, N> {
     type Output = Self;
 
-    fn add(mut self, rhs: Self) -> Self::Output {
-        self.x += rhs.x;
-        self.y += rhs.y;
-        self.z += rhs.z;
+    fn mul(mut self, rhs: T) -> Self::Output {
+        for c in self.coords.iter_mut() {
+            *c = *c * rhs;
+        }
         self
     }
 }
 
-impl std::ops::Sub for Point3D {
+impl<T: Coordinate, const N: usize> Neg for PointN<T, N> {
     type Output = Self;
 
-    fn sub(mut self, rhs: Self) -> Self::Output {
-        self.x -= rhs.x;
-        self.y -= rhs.y;
-        self.z -= rhs.z;
+    fn neg(mut self) -> Self::Output {
+        for c in self.coords.iter_mut() {
+            *c = -*c;
+        }
         self
     }
 }
 
-impl Point {
-    pub fn new(x: i32, y: i32) -> Self {
-        Self { x, y }
+impl<T, const N: usize> Index<usize> for PointN<T, N> {
+    type Output = T;
+
+    fn index(&self, axis: usize) -> &Self::Output {
+        &self.coords[axis]
+    }
+}
+
+impl<T: Coordinate, const N: usize> PointN<T, N> {
+    pub fn from_coords(coords: [T; N]) -> Self {
+        Self { coords }
+    }
+
+    pub fn origin() -> Self {
+        Self::from_coords([T::ZERO; N])
+    }
+
+    pub fn coords(&self) -> [T; N] {
+        self.coords
+    }
+
+    pub fn manhattan_distance(&self, other: &Self) -> T {
+        self.coords
+            .iter()
+            .zip(other.coords)
+            .fold(T::ZERO, |d, (&a, b)| d + (a - b).abs())
+    }
+
+    /// The largest distance along any one axis, i.e. king moves on a grid
+    pub fn chebyshev_distance(&self, other: &Self) -> T {
+        self.coords
+            .iter()
+            .zip(other.coords)
+            .fold(T::ZERO, |d, (&a, b)| d.max((a - b).abs()))
+    }
+
+    /// The smallest coordinate along each axis, e.g. for the corner of a bounding box
+    pub fn component_min(&self, other: &Self) -> Self {
+        self.zip_with(other, T::min)
+    }
+
+    pub fn component_max(&self, other: &Self) -> Self {
+        self.zip_with(other, T::max)
+    }
+
+    pub fn checked_add(&self, rhs: &Self) -> Option<Self> {
+        self.checked_zip_with(rhs, T::checked_add)
+    }
+
+    pub fn checked_sub(&self, rhs: &Self) -> Option<Self> {
+        self.checked_zip_with(rhs, T::checked_sub)
     }
 
-    pub fn shift(&mut self, x: i32, y: i32) {
-        self.x += x;
-        self.y += y;
+    pub fn checked_manhattan_distance(&self, other: &Self) -> Option<T> {
+        self.checked_sub(other)?
+            .coords
+            .iter()
+            .try_fold(T::ZERO, |d, c| d.checked_add(c.checked_abs()?))
     }
 
-    pub fn x(&self) -> i32 {
-        self.x
+    /// Converts to another coordinate type, `None` if a coordinate doesn't fit
+    pub fn try_cast<U: Coordinate + TryFrom<T>>(&self) -> Option<PointN<U, N>> {
+        let mut coords = [U::ZERO; N];
+        for (c, &t) in coords.iter_mut().zip(self.coords.iter()) {
+            *c = t.try_into().ok()?;
+        }
+        Some(PointN::from_coords(coords))
     }
 
-    pub fn y(&self) -> i32 {
-        self.y
+    /// The `2 * N` points one step away along a single axis
+    pub fn orthogonal_neighbors(&self) -> impl Iterator<Item = Self> {
+        let p = *self;
+        (0..N).flat_map(move |axis| {
+            [T::ZERO - T::ONE, T::ONE].into_iter().map(move |d| {
+                let mut n = p;
+                n.coords[axis] = n.coords[axis] + d;
+                n
+            })
+        })
     }
 
-    pub fn manhattan_distance(&self, other: &Point) -> i32 {
-        (self.x() - other.x()).abs() + (self.y() - other.y()).abs()
+    /// The `3^N - 1` points touching this one, including diagonally
+    pub fn neighbors(&self) -> impl Iterator<Item = Self> {
+        let p = *self;
+        // each offset is a base 3 number, one digit per axis, 0, 1, 2 meaning -1, 0, +1
+        (0..3usize.pow(N as u32))
+            // the middle number is all 1s, i.e. the point itself
+            .filter(|&i| i != (3usize.pow(N as u32) - 1) / 2)
+            .map(move |mut i| {
+                let mut n = p;
+                for c in n.coords.iter_mut() {
+                    *c = match i % 3 {
+                        0 => *c - T::ONE,
+                        1 => *c,
+                        _ => *c + T::ONE,
+                    };
+                    i /= 3;
+                }
+                n
+            })
+    }
+
+    fn zip_with<F: Fn(T, T) -> T>(&self, other: &Self, f: F) -> Self {
+        let mut coords = self.coords;
+        for (c, &o) in coords.iter_mut().zip(other.coords.iter()) {
+            *c = f(*c, o);
+        }
+        Self::from_coords(coords)
+    }
+
+    fn checked_zip_with<F: Fn(T, T) -> Option<T>>(&self, other: &Self, f: F) -> Option<Self> {
+        let mut coords = self.coords;
+        for (c, &o) in coords.iter_mut().zip(other.coords.iter()) {
+            *c = f(*c, o)?;
+        }
+        Some(Self::from_coords(coords))
     }
 }
 
-impl<T: TryInto<i32>> From<(T, T)> for Point
-where
-    T::Error: std::fmt::Debug,
-{
+impl<T: Coordinate, const N: usize> From<[T; N]> for PointN<T, N> {
+    fn from(coords: [T; N]) -> Self {
+        Self::from_coords(coords)
+    }
+}
+
+impl<T: Coordinate> PointN<T, 2> {
+    pub fn new(x: T, y: T) -> Self {
+        Self::from_coords([x, y])
+    }
+
+    pub fn shift(&mut self, x: T, y: T) {
+        *self += Self::new(x, y);
+    }
+
+    pub fn x(&self) -> T {
+        self.coords[0]
+    }
+
+    pub fn y(&self) -> T {
+        self.coords[1]
+    }
+
+    pub fn neighbors4(&self) -> impl Iterator<Item = Self> {
+        self.orthogonal_neighbors()
+    }
+
+    pub fn neighbors8(&self) -> impl Iterator<Item = Self> {
+        self.neighbors()
+    }
+}
+
+impl<T: Coordinate> From<(T, T)> for PointN<T, 2> {
     fn from((x, y): (T, T)) -> Self {
-        Self::new(x.try_into().unwrap(), y.try_into().unwrap())
+        Self::new(x, y)
     }
 }
 
-impl Point3D {
-    pub fn new(x: i32, y: i32, z: i32) -> Self {
-        Self { x, y, z }
+impl<T: Coordinate> PointN<T, 3> {
+    pub fn new(x: T, y: T, z: T) -> Self {
+        Self::from_coords([x, y, z])
+    }
+
+    pub fn shift(&mut self, x: T, y: T, z: T) {
+        *self += Self::new(x, y, z);
     }
 
-    pub fn shift(&mut self, x: i32, y: i32, z: i32) {
-        self.x += x;
-        self.y += y;
-        self.z += z;
+    pub fn x(&self) -> T {
+        self.coords[0]
     }
 
-    pub fn x(&self) -> i32 {
-        self.x
+    pub fn y(&self) -> T {
+        self.coords[1]
     }
 
-    pub fn y(&self) -> i32 {
-        self.y
+    pub fn z(&self) -> T {
+        self.coords[2]
     }
 
-    pub fn z(&self) -> i32 {
-        self.z
+    pub fn neighbors6(&self) -> impl Iterator<Item = Self> {
+        self.orthogonal_neighbors()
     }
 
-    pub fn manhattan_distance(&self, other: &Point3D) -> i32 {
-        (self.x() - other.x()).abs() + (self.y() - other.y()).abs() + (self.z() - other.z()).abs()
+    pub fn neighbors26(&self) -> impl Iterator<Item = Self> {
+        self.neighbors()
     }
 }
 
-impl<T: TryInto<i32>> From<(T, T, T)> for Point3D
-where
-    T::Error: std::fmt::Debug,
-{
+impl<T: Coordinate> From<(T, T, T)> for PointN<T, 3> {
     fn from((x, y, z): (T, T, T)) -> Self {
-        Self::new(
-            x.try_into().unwrap(),
-            y.try_into().unwrap(),
-            z.try_into().unwrap(),
-        )
+        Self::new(x, y, z)
+    }
+}
+
+impl<T: Coordinate> PointN<T, 4> {
+    pub fn new(x: T, y: T, z: T, w: T) -> Self {
+        Self::from_coords([x, y, z, w])
+    }
+
+    pub fn x(&self) -> T {
+        self.coords[0]
+    }
+
+    pub fn y(&self) -> T {
+        self.coords[1]
+    }
+
+    pub fn z(&self) -> T {
+        self.coords[2]
+    }
+
+    pub fn w(&self) -> T {
+        self.coords[3]
+    }
+}
+
+/// A step on a plane. `unit` has `Up` as +y, the way ropes and falling rocks move; `grid_unit`
+/// has it as -y, for grids read from the input where y grows downwards.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Direction {
+    Up,
+    Right,
+    Down,
+    Left,
+}
+
+impl Direction {
+    /// Clockwise, starting from `Up`
+    pub const ALL: [Direction; 4] = [
+        Direction::Up,
+        Direction::Right,
+        Direction::Down,
+        Direction::Left,
+    ];
+
+    pub fn cw(self) -> Self {
+        match self {
+            Direction::Up => Direction::Right,
+            Direction::Right => Direction::Down,
+            Direction::Down => Direction::Left,
+            Direction::Left => Direction::Up,
+        }
+    }
+
+    pub fn ccw(self) -> Self {
+        match self {
+            Direction::Up => Direction::Left,
+            Direction::Right => Direction::Up,
+            Direction::Down => Direction::Right,
+            Direction::Left => Direction::Down,
+        }
+    }
+
+    pub fn reverse(self) -> Self {
+        self.cw().cw()
+    }
+
+    pub fn unit<T: Coordinate>(self) -> Point<T> {
+        match self {
+            Direction::Up => Point::new(T::ZERO, T::ONE),
+            Direction::Right => Point::new(T::ONE, T::ZERO),
+            Direction::Down => Point::new(T::ZERO, -T::ONE),
+            Direction::Left => Point::new(-T::ONE, T::ZERO),
+        }
+    }
+
+    /// The step on a grid read from the input, where `Up` is the row above
+    pub fn grid_unit<T: Coordinate>(self) -> Point<T> {
+        let unit = self.unit::<T>();
+        Point::new(unit.x(), -unit.y())
+    }
+
+    /// The direction of a step of one square on a grid read from the input
+    pub fn from_grid_unit<T: Coordinate>(step: Point<T>) -> Option<Self> {
+        Direction::ALL.into_iter().find(|d| d.grid_unit() == step)
+    }
+
+    /// As drawn on a map, `^` being up
+    pub fn arrow(self) -> char {
+        match self {
+            Direction::Up => '^',
+            Direction::Right => '>',
+            Direction::Down => 'v',
+            Direction::Left => '<',
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidDirection(pub String);
+
+impl std::fmt::Display for InvalidDirection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "invalid direction {:?}", self.0)
+    }
+}
+
+impl std::error::Error for InvalidDirection {}
+
+/// Accepts letters (`U`, `R`, `D`, `L` and compass points) and arrows (`^`, `>`, `v`, `<`)
+impl TryFrom<char> for Direction {
+    type Error = InvalidDirection;
+
+    fn try_from(c: char) -> Result<Self, Self::Error> {
+        match c {
+            'U' | 'u' | 'N' | 'n' | '^' => Ok(Direction::Up),
+            'R' | 'r' | 'E' | 'e' | '>' => Ok(Direction::Right),
+            'D' | 'd' | 'S' | 's' | 'v' | 'V' => Ok(Direction::Down),
+            'L' | 'l' | 'W' | 'w' | '<' => Ok(Direction::Left),
+            c => Err(InvalidDirection(c.to_string())),
+        }
+    }
+}
+
+impl FromStr for Direction {
+    type Err = InvalidDirection;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chars = s.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => Direction::try_from(c),
+            _ => Err(InvalidDirection(s.to_string())),
+        }
     }
 }
+
+/// Fallible conversions from tuples and arrays of any other integer type, e.g. `usize` grid
+/// indices
+macro_rules! try_from_integers {
+    ($to:ty => $($from:ty),*) => {
+        $(
+            impl<const N: usize> TryFrom<[$from; N]> for PointN<$to, N> {
+                type Error = TryFromIntError;
+
+                fn try_from(coords: [$from; N]) -> Result<Self, Self::Error> {
+                    let mut converted = [0; N];
+                    for (c, v) in converted.iter_mut().zip(coords) {
+                        *c = v.try_into()?;
+                    }
+                    Ok(Self::from_coords(converted))
+                }
+            }
+
+            impl TryFrom<($from, $from)> for PointN<$to, 2> {
+                type Error = TryFromIntError;
+
+                fn try_from((x, y): ($from, $from)) -> Result<Self, Self::Error> {
+                    Self::try_from([x, y])
+                }
+            }
+
+            impl TryFrom<($from, $from, $from)> for PointN<$to, 3> {
+                type Error = TryFromIntError;
+
+                fn try_from((x, y, z): ($from, $from, $from)) -> Result<Self, Self::Error> {
+                    Self::try_from([x, y, z])
+                }
+            }
+        )*
+    };
+}
+
+try_from_integers!(i8 => u8, u16, u32, u64, u128, usize, i16, i32, i64, i128, isize);
+try_from_integers!(i16 => u8, u16, u32, u64, u128, usize, i8, i32, i64, i128, isize);
+try_from_integers!(i32 => u8, u16, u32, u64, u128, usize, i8, i16, i64, i128, isize);
+try_from_integers!(i64 => u8, u16, u32, u64, u128, usize, i8, i16, i32, i128, isize);
+try_from_integers!(i128 => u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, isize);
+try_from_integers!(isize => u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128);
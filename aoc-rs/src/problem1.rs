@@ -1,7 +1,16 @@
-use crate::Problem;
+use crate::{top::TopK, Explanation, Metadata, Part, Problem, Status, Tag};
 
 pub struct Problem1;
 impl Problem for Problem1 {
+    fn metadata(&self) -> Metadata {
+        Metadata {
+            title: "Calorie Counting",
+            part1: Status::Solved,
+            part2: Status::Solved,
+            tags: &[Tag::Parsing],
+        }
+    }
+
     fn solve_part1(&mut self, lines: &[String]) -> String {
         let packs = self.read_packs(lines);
         packs
@@ -14,16 +23,39 @@ impl Problem for Problem1 {
 
     fn solve_part2(&mut self, lines: &[String]) -> String {
         let packs = self.read_packs(lines);
-        let mut sums: Vec<_> = packs.iter().map(|pack| pack.iter().sum()).collect();
-        sums.sort_unstable();
-        sums.iter().rev().take(3).sum::<i64>().to_string()
+        packs
+            .iter()
+            .map(|pack| pack.iter().sum::<i64>())
+            .top_k(3)
+            .into_iter()
+            .map(|(_, sum)| sum)
+            .sum::<i64>()
+            .to_string()
+    }
+
+    fn explain(&mut self, part: Part, lines: &[String]) -> Option<Explanation> {
+        let n = match part {
+            Part::One => 1,
+            Part::Two => 3,
+        };
+
+        // elves are numbered from 1, in the order their packs appear
+        Some(
+            self.read_packs(lines)
+                .iter()
+                .map(|pack| pack.iter().sum::<i64>())
+                .top_k(n)
+                .into_iter()
+                .fold(Explanation::new(), |e, (i, sum)| {
+                    e.with(format!("elf {}", i + 1), sum)
+                }),
+        )
     }
 }
 
 impl Problem1 {
     fn read_packs(&self, lines: &[String]) -> Vec<Vec<i64>> {
         lines
-            .to_vec()
             .split(|s| matches!(s.as_str(), ""))
             .map(|ss| ss.iter().map(|s| s.parse::<i64>().unwrap()).collect())
             .collect()
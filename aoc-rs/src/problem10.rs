@@ -1,4 +1,4 @@
-use crate::Problem;
+use crate::{grid::Grid, point::Point, recording, Metadata, Problem, Status, Tag};
 
 enum Instructions {
     Noop,
@@ -63,35 +63,31 @@ impl Processor {
 }
 
 struct Monitor {
-    monitor: [[bool; 40]; 6],
+    monitor: Grid<bool>,
 }
 
 impl Monitor {
     fn new() -> Self {
         Monitor {
-            monitor: [[false; 40]; 6],
+            monitor: Grid::new(40, 6, false),
         }
     }
 
     fn cycle(&mut self, processor: &Processor) {
         let c = processor.cycle_count() % 240;
         let pos = processor.rax();
-        self.monitor[(c / 40) as usize][c % 40] = ((c % 40) as i64 - pos).abs() <= 1;
+        self.monitor[Point::try_from((c % 40, c / 40)).unwrap()] =
+            ((c % 40) as i64 - pos).abs() <= 1;
     }
+}
 
-    fn draw(&self) {
-        for r in self.monitor.iter() {
-            r.iter().for_each(|c| {
-                print!(
-                    "{}",
-                    match c {
-                        true => "#",
-                        false => ".",
-                    }
-                )
-            });
-            println!();
-        }
+impl std::fmt::Display for Monitor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let pixels = self.monitor.map(|c| match c {
+            true => '#',
+            false => '.',
+        });
+        write!(f, "{}", pixels)
     }
 }
 
@@ -111,6 +107,17 @@ impl Computer {
     fn cycle(&mut self) {
         self.monitor.cycle(&self.processor);
         self.processor.cycle();
+        recording::frame(|| self.draw());
+    }
+
+    /// The screen so far and the registers under it
+    fn draw(&self) -> String {
+        format!(
+            "{}\ncycle {:>3}  X={}\n",
+            self.monitor,
+            self.processor.cycle_count(),
+            self.processor.rax()
+        )
     }
 
     fn iter_mut(&mut self) -> Clock<'_> {
@@ -152,21 +159,23 @@ fn read_input(lines: &[String]) -> Vec<Instructions> {
 
 pub struct Problem10;
 impl Problem for Problem10 {
+    fn metadata(&self) -> Metadata {
+        Metadata {
+            title: "Cathode-Ray Tube",
+            part1: Status::Solved,
+            part2: Status::Solved,
+            tags: &[Tag::Simulation],
+        }
+    }
+
     fn solve_part1(&mut self, lines: &[String]) -> String {
         let mut computer = Computer::new(read_input(lines));
         computer
             .iter_mut()
-            .map(|p| {
-                println!("{:?}", p);
-                p
-            })
             .skip(19)
             .step_by(40)
             .take(6)
-            .map(|(c, v)| {
-                println!("({}, {})", c, v);
-                (c as i64) * v
-            })
+            .map(|(c, v)| (c as i64) * v)
             .sum::<i64>()
             .to_string()
     }
@@ -174,14 +183,7 @@ impl Problem for Problem10 {
     fn solve_part2(&mut self, lines: &[String]) -> String {
         let mut computer = Computer::new(read_input(lines));
         let _c = computer.iter_mut().count();
-        computer.monitor.draw();
-        println!(
-            "{}, {}, {}",
-            computer.processor.ip,
-            computer.processor.instructions.len(),
-            computer.processor.cycle_count()
-        );
-
-        "".to_string()
+        // the answer is read off the screen, start it on its own line
+        format!("\n{}", computer.monitor)
     }
 }
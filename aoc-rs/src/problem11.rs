@@ -1,65 +1,67 @@
-use std::collections::HashSet;
+use crate::{
+    cycles, math,
+    parse::{
+        delimited, either, integer, literal, map, parse_lines, preceded, records, separated0,
+        terminated, value, PResult,
+    },
+    top::TopK,
+    Metadata, Part, Problem, Status, Tag, Variant,
+};
 
-use crate::Problem;
+fn operation(input: &str) -> PResult<'_, Operation> {
+    preceded(
+        literal("  Operation: new = old "),
+        either(
+            value(literal("* old"), Operation::Square),
+            either(
+                map(preceded(literal("* "), integer()), Operation::Multiply),
+                map(preceded(literal("+ "), integer()), Operation::Add),
+            ),
+        ),
+    )(input)
+}
+
+fn monkey(input: &str) -> PResult<'_, Monkey> {
+    let (_, input) = delimited(literal("Monkey "), integer::<usize>(), literal(":\n"))(input)?;
+    let (items, input) = delimited(
+        literal("  Starting items: "),
+        separated0(integer(), literal(", ")),
+        literal("\n"),
+    )(input)?;
+    let (operation, input) = terminated(operation, literal("\n"))(input)?;
+    let (test, input) =
+        delimited(literal("  Test: divisible by "), integer(), literal("\n"))(input)?;
+    let (true_target, input) = delimited(
+        literal("    If true: throw to monkey "),
+        integer(),
+        literal("\n"),
+    )(input)?;
+    let (false_target, input) =
+        preceded(literal("    If false: throw to monkey "), integer())(input)?;
+
+    Ok((
+        Monkey {
+            items,
+            operation,
+            test,
+            true_target,
+            false_target,
+            inspections: 0,
+            worry_relief: false,
+        },
+        input,
+    ))
+}
 
 fn read_input(lines: &[String], worry_relief: bool) -> Vec<Monkey> {
-    lines
-        .chunks(7)
-        .map(|ls| {
-            let mut iter = ls.iter().skip(1);
-            // just going to lazy parse this
-            let items: Vec<i64> = iter
-                .next()
-                .unwrap()
-                .trim_start_matches("  Starting items: ")
-                .split(", ")
-                .map(|s| s.parse().unwrap())
-                .collect();
-            let operation = iter
-                .next()
-                .unwrap()
-                .trim_start_matches("  Operation: new = old ")
-                .split_once(' ')
-                .map(|(op, v)| match op {
-                    "+" => Operation::Add(v.parse().unwrap()),
-                    "*" if v != "old" => Operation::Multiply(v.parse().unwrap()),
-                    "*" => Operation::Square,
-                    _ => panic!("invalid operation"),
-                })
-                .unwrap();
-            let test: i64 = iter
-                .next()
-                .unwrap()
-                .trim_start_matches("  Test: divisible by ")
-                .parse()
-                .unwrap();
-            let true_target: usize = iter
-                .next()
-                .unwrap()
-                .trim_start_matches("    If true: throw to monkey ")
-                .parse()
-                .unwrap();
-            let false_target: usize = iter
-                .next()
-                .unwrap()
-                .trim_start_matches("    If false: throw to monkey ")
-                .parse()
-                .unwrap();
-
-            Monkey {
-                items,
-                operation,
-                test,
-                true_target,
-                false_target,
-                inspections: 0,
-                worry_relief,
-            }
-        })
-        .collect()
+    let mut monkeys = parse_lines(records(monkey), lines).expect("invalid input");
+    monkeys
+        .iter_mut()
+        .for_each(|m| m.worry_relief = worry_relief);
+    monkeys
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 enum Operation {
     Add(i64),
     Multiply(i64),
@@ -67,11 +69,21 @@ enum Operation {
 }
 
 impl Operation {
-    fn apply(&self, v: i64) -> i64 {
-        match self {
-            Operation::Add(n) => v + n,
-            Operation::Multiply(n) => v * n,
-            Operation::Square => v * v,
+    /// The new worry level, modulo `ring` if given. Without one, worry levels too big for an
+    /// `i64` panic rather than wrap around.
+    fn apply(&self, v: i64, ring: Option<i64>) -> i64 {
+        match ring {
+            Some(ring) => match self {
+                Operation::Add(n) => math::add_mod(v, *n, ring),
+                Operation::Multiply(n) => math::mul_mod(v, *n, ring),
+                Operation::Square => math::mul_mod(v, v, ring),
+            },
+            None => match self {
+                Operation::Add(n) => v.checked_add(*n),
+                Operation::Multiply(n) => v.checked_mul(*n),
+                Operation::Square => v.checked_mul(v),
+            }
+            .expect("worry level overflowed"),
         }
     }
 }
@@ -88,13 +100,14 @@ struct Monkey {
 }
 
 impl Monkey {
-    fn turn(&mut self) -> Vec<(usize, i64)> {
+    /// Inspects and throws every item, worry levels are kept modulo `ring` if given
+    fn turn(&mut self, ring: Option<i64>) -> Vec<(usize, i64)> {
         let throw = self
             .items
             .iter()
             .map(|item| {
                 self.inspections += 1;
-                let mut item = self.operation.apply(*item);
+                let mut item = self.operation.apply(*item, ring);
                 if self.worry_relief {
                     item /= 3;
                 }
@@ -110,53 +123,98 @@ impl Monkey {
     }
 }
 
+/// Every monkey takes a turn, worry levels are kept modulo `ring` if given
+fn round(monkeys: &mut [Monkey], ring: Option<i64>) {
+    for i in 0..monkeys.len() {
+        for (target, item) in monkeys[i].turn(ring) {
+            monkeys[target].items.push(item);
+        }
+    }
+}
+
+/// Every test divides the LCM of the tests, so worry levels modulo the LCM still pass the same
+/// tests
+fn ring(monkeys: &[Monkey]) -> i64 {
+    math::lcm_all(monkeys.iter().map(|m| m.test)).expect("the tests' LCM overflows an i64")
+}
+
+/// The two highest inspection counts multiplied together
+fn monkey_business(inspections: impl IntoIterator<Item = usize>) -> usize {
+    inspections
+        .into_iter()
+        .top_k(2)
+        .into_iter()
+        .map(|(_, n)| n)
+        .product::<usize>()
+}
+
 pub struct Problem11;
 impl Problem for Problem11 {
+    fn metadata(&self) -> Metadata {
+        Metadata {
+            title: "Monkey in the Middle",
+            part1: Status::Solved,
+            part2: Status::Solved,
+            tags: &[Tag::Parsing, Tag::Simulation, Tag::Math],
+        }
+    }
+
     fn solve_part1(&mut self, lines: &[String]) -> String {
         let mut monkeys = read_input(lines, true);
-        let l = monkeys.len();
-
         for _ in 0..20 {
-            for i in 0..l {
-                for (target, item) in monkeys[i].turn() {
-                    monkeys[target].items.push(item);
-                }
-            }
+            round(&mut monkeys, None);
         }
 
-        monkeys.sort_by_key(|m| m.inspections);
-        monkeys
-            .into_iter()
-            .rev()
-            .map(|m| m.inspections)
-            .take(2)
-            .product::<usize>()
-            .to_string()
+        monkey_business(monkeys.iter().map(|m| m.inspections)).to_string()
     }
 
     fn solve_part2(&mut self, lines: &[String]) -> String {
         let mut monkeys = read_input(lines, false);
-        let ring: i64 = {
-            let tests: HashSet<_> = monkeys.iter().map(|m| m.test).collect();
-            tests.into_iter().product()
-        };
-        let l = monkeys.len();
-
+        let ring = ring(&monkeys);
         for _ in 0..10000 {
-            for i in 0..l {
-                for (target, item) in monkeys[i].turn() {
-                    monkeys[target].items.push(item % ring);
-                }
-            }
+            round(&mut monkeys, Some(ring));
         }
 
-        monkeys.sort_by_key(|m| m.inspections);
-        monkeys
-            .into_iter()
-            .rev()
-            .map(|m| m.inspections)
-            .take(2)
-            .product::<usize>()
-            .to_string()
+        monkey_business(monkeys.iter().map(|m| m.inspections)).to_string()
     }
+
+    fn variants(&self, part: Part) -> Vec<Variant> {
+        match part {
+            Part::One => vec![],
+            Part::Two => vec![Variant {
+                name: "cycle",
+                solve: with_cycle,
+            }],
+        }
+    }
+}
+
+/// Stops as soon as the items are where they were after an earlier round, extrapolating every
+/// monkey's inspections from there
+fn with_cycle(lines: &[String]) -> String {
+    const ROUNDS: usize = 10000;
+
+    let mut monkeys = read_input(lines, false);
+    let ring = ring(&monkeys);
+    let (cycle, history) = cycles::run(
+        &mut monkeys,
+        ROUNDS,
+        |monkeys| round(monkeys, Some(ring)),
+        |monkeys| monkeys.iter().map(|m| m.items.clone()).collect::<Vec<_>>(),
+        |monkeys| {
+            monkeys
+                .iter()
+                .map(|m| m.inspections as i64)
+                .collect::<Vec<_>>()
+        },
+    );
+
+    let inspections = (0..monkeys.len()).map(|i| {
+        let history: Vec<_> = history.iter().map(|h| h[i]).collect();
+        match cycle {
+            Some(cycle) => cycle.extrapolate(&history, ROUNDS as u64) as usize,
+            None => history[ROUNDS] as usize,
+        }
+    });
+    monkey_business(inspections).to_string()
 }
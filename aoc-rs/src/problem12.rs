@@ -1,120 +1,187 @@
-use std::collections::VecDeque;
+use itertools::Itertools;
 
-use crate::point::Point;
-use crate::Problem;
+use crate::{
+    grid::Grid,
+    image::{Image, Rgb},
+    point::{Direction, Point},
+    search::{self, Search},
+    terminal::{Canvas, Cell, Color},
+    Explanation, Metadata, Part, Problem, Status, Tag, Variant,
+};
 
 /// Outputs the start point, the end point, and the grid of mountains
-fn read_input(lines: &[String]) -> (Point, Point, Vec<Vec<i32>>) {
-    let mut start = (0, 0);
-    let mut end = (0, 0);
-    let grid = lines
-        .iter()
-        .enumerate()
-        .map(|(y, l)| {
-            l.chars()
-                .enumerate()
-                .map(|(x, c)| match c {
-                    'S' => {
-                        start = (x, y);
-                        0
-                    }
-                    'E' => {
-                        end = (x, y);
-                        25
-                    }
-                    _ => ((c as u8) - b'a') as i32,
-                })
-                .collect()
-        })
-        .collect();
-
-    (start.into(), end.into(), grid)
+fn read_input(lines: &[String]) -> (Point, Point, Grid<i32>) {
+    let chars = Grid::parse(lines, |c| c);
+    let start = chars.position(|&c| c == 'S').unwrap();
+    let end = chars.position(|&c| c == 'E').unwrap();
+    let grid = chars.map(|&c| match c {
+        'S' => 0,
+        'E' => 25,
+        _ => ((c as u8) - b'a') as i32,
+    });
+
+    (start, end, grid)
 }
 
-fn available_moves(p: &Point, grid: &[Vec<i32>], exploration: &[Vec<Option<i32>>]) -> Vec<Point> {
-    let (lx, ly) = (grid[0].len() as i32, grid.len() as i32);
-    [(-1, 0), (1, 0), (0, -1), (0, 1)]
-        .into_iter()
-        .filter_map(|(x, y)| {
-            let n = *p + (x, y).into();
-
-            let (x, y) = (n.x(), n.y());
-
-            if x < 0
-                || y < 0
-                || x >= lx
-                || y >= ly
-                // if the next square is more than 1 higher than the current square
-                || (grid[y as usize][x as usize] - grid[p.y() as usize][p.x() as usize]) > 1
-                // if the next square's score is not None and its value is <= to
-                // what this path's score would be
-                || exploration[y as usize][x as usize].map_or(false, |n_score| {
-                    n_score <= exploration[p.y() as usize][p.x() as usize].unwrap() + 1
-                })
-            {
-                // do nothing
-                None
-            } else {
-                Some(n)
-            }
-        })
-        .collect()
+/// Whether a step from the first height to the second is allowed
+type StepRule = fn(i32, i32) -> bool;
+
+/// Climbing from the start, a step can be at most 1 higher
+fn climb(from: i32, to: i32) -> bool {
+    to - from <= 1
 }
 
-fn explore(grid: &Vec<Vec<i32>>, start: Point) -> Vec<Vec<Option<i32>>> {
-    let mut exploration: Vec<Vec<Option<i32>>> =
-        std::iter::repeat(std::iter::repeat(None).take(grid[0].len()))
-            .take(grid.len())
-            .map(|r| r.collect())
-            .collect();
-
-    let mut moves = VecDeque::new();
-    exploration[start.y() as usize][start.x() as usize] = Some(0);
-    moves.push_back(start);
-    while !moves.is_empty() {
-        let next = moves.pop_front().unwrap();
-        let available = available_moves(&next, grid, &exploration);
-
-        // we could just map it, but we want to panic! so we can see errors
-        let s = exploration[next.y() as usize][next.x() as usize].unwrap();
-        for m in available {
-            exploration[m.y() as usize][m.x() as usize] = Some(s + 1);
-            moves.push_back(m);
-        }
+/// Walking back from the end, a step can be at most 1 lower
+fn descend(from: i32, to: i32) -> bool {
+    from - to <= 1
+}
+
+/// The squares one step away from `p` that `can_step` allows
+fn moves(grid: &Grid<i32>, can_step: StepRule) -> impl Fn(&Point) -> Vec<Point> + '_ {
+    move |&p| {
+        p.neighbors4()
+            .filter(|&n| grid.contains(n) && can_step(grid[p], grid[n]))
+            .collect()
     }
+}
+
+/// The shortest climb from any of `starts` to `end`
+fn climb_from(
+    grid: &Grid<i32>,
+    starts: impl IntoIterator<Item = Point>,
+    end: Point,
+) -> Search<Point, usize> {
+    search::bfs(starts, moves(grid, climb), |&p| p == end)
+}
+
+fn lowest_squares(grid: &Grid<i32>) -> impl Iterator<Item = Point> + '_ {
+    grid.iter()
+        .filter(|(_, &height)| height == 0)
+        .map(|(p, _)| p)
+}
 
-    exploration
+/// The climb each part's default solver finds
+fn shortest_climb(part: Part, grid: &Grid<i32>, start: Point, end: Point) -> Search<Point, usize> {
+    match part {
+        Part::One => climb_from(grid, [start], end),
+        Part::Two => climb_from(grid, lowest_squares(grid), end),
+    }
 }
 
 pub struct Problem12;
 impl Problem for Problem12 {
+    fn metadata(&self) -> Metadata {
+        Metadata {
+            title: "Hill Climbing Algorithm",
+            part1: Status::Solved,
+            part2: Status::Solved,
+            tags: &[Tag::Grid, Tag::Graph, Tag::Search],
+        }
+    }
+
     fn solve_part1(&mut self, lines: &[String]) -> String {
         let (start, end, grid) = read_input(lines);
-        let explored_grid = explore(&grid, start);
-
-        explored_grid[end.y() as usize][end.x() as usize]
+        climb_from(&grid, [start], end)
+            .goal_cost()
             .unwrap()
             .to_string()
     }
 
     fn solve_part2(&mut self, lines: &[String]) -> String {
-        // Note: there's an optimization in here to use the same explored grid over and over
-        // but the input so small that it will still run fast, regardless
+        // climbing from every 'a' at once, the first to reach the end is the best start
         let (_, end, grid) = read_input(lines);
-        let choices = grid.iter().enumerate().flat_map(|(y, row)| {
-            row.iter()
-                .enumerate()
-                .filter_map(move |(x, height)| match *height == 0 {
-                    true => Some(Point::from((x, y))),
-                    false => None,
-                })
-        });
-
-        choices
-            .into_iter()
-            .filter_map(|start| explore(&grid, start)[end.y() as usize][end.x() as usize])
-            .min()
+        climb_from(&grid, lowest_squares(&grid), end)
+            .goal_cost()
             .unwrap()
             .to_string()
     }
+
+    fn explain(&mut self, part: Part, lines: &[String]) -> Option<Explanation> {
+        let (start, end, grid) = read_input(lines);
+        let path = shortest_climb(part, &grid, start, end).path()?;
+
+        Some(
+            Explanation::new()
+                .with("start", format!("({}, {})", path[0].x(), path[0].y()))
+                .with("steps", (path.len() - 1).to_string())
+                .with("route", route(&path)),
+        )
+    }
+
+    /// The height map from black for 'a' to white for 'z', with the shortest climb in red
+    fn render(&mut self, part: Part, lines: &[String]) -> Option<Image> {
+        let (start, end, grid) = read_input(lines);
+        let mut image = Image::from_grid(&grid, |&height| Rgb::shade(height as i64, 0, 25));
+        for p in shortest_climb(part, &grid, start, end).path()? {
+            image[p] = Rgb::RED;
+        }
+        Some(image)
+    }
+
+    /// The height map with the shortest climb in red
+    fn show(&mut self, part: Part, lines: &[String]) -> Option<Canvas> {
+        let mut canvas = Canvas::from_grid(&Grid::parse(lines, |c| c), |&c| c);
+        let (start, end, grid) = read_input(lines);
+        for p in shortest_climb(part, &grid, start, end).path()? {
+            canvas.highlight(p, Color::Red, '*');
+        }
+        canvas.set_focus(end);
+        Some(canvas.with_legend(
+            Cell::colored('a', Color::Red).with_marker('*'),
+            "on the shortest climb",
+        ))
+    }
+
+    fn variants(&self, part: Part) -> Vec<Variant> {
+        match part {
+            Part::One => vec![],
+            Part::Two => vec![
+                Variant {
+                    name: "every-start",
+                    solve: every_start,
+                },
+                Variant {
+                    name: "descend",
+                    solve: descend_from_end,
+                },
+            ],
+        }
+    }
+}
+
+/// Climbs from every possible starting square, one search each
+fn every_start(lines: &[String]) -> String {
+    let (_, end, grid) = read_input(lines);
+
+    lowest_squares(&grid)
+        .filter_map(|start| climb_from(&grid, [start], end).goal_cost())
+        .min()
+        .unwrap()
+        .to_string()
+}
+
+/// Walks down from the end once, exploring everything, the closest 'a' is the best start
+fn descend_from_end(lines: &[String]) -> String {
+    let (_, end, grid) = read_input(lines);
+    let search = search::bfs([end], moves(&grid, descend), |_| false);
+
+    lowest_squares(&grid)
+        .filter_map(|p| search.cost(&p))
+        .min()
+        .unwrap()
+        .to_string()
+}
+
+/// The path as runs of moves on the map, e.g. `v2 >3 ^1`
+fn route(path: &[Point]) -> String {
+    let arrows = path.windows(2).map(|w| {
+        Direction::from_grid_unit(w[1] - w[0])
+            .expect("the path moves a square at a time")
+            .arrow()
+    });
+
+    arrows
+        .dedup_with_count()
+        .map(|(count, c)| format!("{}{}", c, count))
+        .join(" ")
 }
@@ -1,6 +1,14 @@
 use std::cmp::Ordering;
 
-use crate::Problem;
+use itertools::Itertools;
+
+use crate::{
+    parse::{
+        delimited, either, integer, literal, map, parse_lines, records, separated0, separated1,
+        PResult,
+    },
+    Explanation, Metadata, Part, Problem, Status, Tag,
+};
 
 #[derive(Debug, PartialEq, Eq, Clone)]
 enum Element {
@@ -26,42 +34,28 @@ impl From<Vec<Element>> for Element {
     }
 }
 
-fn parse_line(l: &str) -> Element {
-    let mut lists_stack = vec![vec![]];
-    let mut iter = l.chars().peekable();
-    iter.next();
-    iter.next_back();
-    while iter.peek().is_some() {
-        let c = iter.next().unwrap();
-        match c {
-            '[' => {
-                lists_stack.push(vec![]);
-            }
-            ']' => {
-                // closing of the outermost list
-                if lists_stack.len() == 1 {
-                    break;
-                }
-
-                let v = lists_stack.pop().unwrap();
-                lists_stack.last_mut().unwrap().push(v.into());
-            }
-            ',' => {}
-            _ => {
-                let mut v = vec![c];
-                while iter.peek().map_or(false, |c| c.is_digit(10)) {
-                    v.push(iter.next().unwrap());
-                }
-                let s: String = v.into_iter().collect();
-                lists_stack
-                    .last_mut()
-                    .unwrap()
-                    .push(s.parse::<i32>().unwrap().into());
-            }
-        }
-    }
+fn element(input: &str) -> PResult<'_, Element> {
+    either(
+        map(integer(), Element::Number),
+        map(
+            delimited(
+                literal("["),
+                separated0(element, literal(",")),
+                literal("]"),
+            ),
+            Element::List,
+        ),
+    )(input)
+}
 
-    Element::List(lists_stack.into_iter().next().unwrap())
+/// Every packet, in order, skipping the blank lines between pairs
+fn read_packets(lines: &[String]) -> Vec<Element> {
+    let pair = separated1(element, literal("\n"));
+    parse_lines(records(pair), lines)
+        .expect("invalid input")
+        .into_iter()
+        .flatten()
+        .collect()
 }
 
 impl PartialOrd for Element {
@@ -94,52 +88,76 @@ impl Ord for Element {
     }
 }
 
+/// 1-based indices of the pairs that are in the right order
+fn ordered_pairs(lines: &[String]) -> Vec<usize> {
+    let elements = read_packets(lines);
+
+    elements
+        .iter()
+        .step_by(2)
+        .zip(elements.iter().skip(1).step_by(2))
+        .enumerate()
+        .filter_map(|(i, (one, two))| match one <= two {
+            true => Some(i + 1),
+            false => None,
+        })
+        .collect()
+}
+
+/// 1-based positions of the divider packets once every packet is sorted
+fn divider_positions(lines: &[String]) -> Vec<usize> {
+    let divider_packets: [Element; 2] = [
+        Element::from(2_i32).lift().lift(),
+        Element::from(6_i32).lift().lift(),
+    ];
+    let mut elements = read_packets(lines);
+    elements.extend(divider_packets.clone());
+
+    elements.sort();
+
+    elements
+        .into_iter()
+        .enumerate()
+        .filter_map(|(i, element)| {
+            match element == divider_packets[0] || element == divider_packets[1] {
+                true => Some(i + 1),
+                false => None,
+            }
+        })
+        .collect()
+}
+
 pub struct Problem13;
 impl Problem for Problem13 {
+    fn metadata(&self) -> Metadata {
+        Metadata {
+            title: "Distress Signal",
+            part1: Status::Solved,
+            part2: Status::Solved,
+            tags: &[Tag::Parsing],
+        }
+    }
+
     fn solve_part1(&mut self, lines: &[String]) -> String {
-        let elements: Vec<_> = lines
-            .iter()
-            .filter(|l| !l.is_empty())
-            .map(|l| parse_line(l))
-            .collect();
-
-        elements
-            .iter()
-            .step_by(2)
-            .zip(elements.iter().skip(1).step_by(2))
-            .enumerate()
-            .filter_map(|(i, (one, two))| match one <= two {
-                true => Some(i + 1),
-                false => None,
-            })
-            .sum::<usize>()
-            .to_string()
+        ordered_pairs(lines).into_iter().sum::<usize>().to_string()
     }
 
     fn solve_part2(&mut self, lines: &[String]) -> String {
-        let divider_packets: [Element; 2] = [
-            Element::from(2_i32).lift().lift(),
-            Element::from(6_i32).lift().lift(),
-        ];
-        let mut elements: Vec<_> = lines
-            .iter()
-            .filter(|l| !l.is_empty())
-            .map(|l| parse_line(l))
-            .chain(divider_packets.clone().into_iter())
-            .collect();
-
-        elements.sort();
-
-        elements
+        divider_positions(lines)
             .into_iter()
-            .enumerate()
-            .filter_map(|(i, element)| {
-                match element == divider_packets[0] || element == divider_packets[1] {
-                    true => Some(i + 1),
-                    false => None,
-                }
-            })
             .product::<usize>()
             .to_string()
     }
+
+    fn explain(&mut self, part: Part, lines: &[String]) -> Option<Explanation> {
+        Some(match part {
+            Part::One => {
+                Explanation::new().with("pairs in order", ordered_pairs(lines).iter().join(", "))
+            }
+            Part::Two => Explanation::new().with(
+                "divider positions",
+                divider_positions(lines).iter().join(", "),
+            ),
+        })
+    }
 }
@@ -1,17 +1,30 @@
-use std::collections::HashSet;
-
 use itertools::Itertools;
 
-use crate::{point::Point, Problem};
+use crate::{
+    grid::Grid,
+    image::{Image, Rgb},
+    point::Point,
+    recording,
+    terminal::{Canvas, Cell, Color},
+    Metadata, Part, Problem, Status, Tag,
+};
 
 const SAND_X: i32 = 500;
 
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Tile {
+    Air,
+    Rock,
+    Sand,
+}
+
 #[derive(Debug)]
 pub struct Board {
-    board: HashSet<Point>,
+    board: Grid<Tile>,
     largest_y: i32,
     part2: bool,
     end: Point,
+    last_settled: Option<Point>,
 }
 
 impl Board {
@@ -21,12 +34,71 @@ impl Board {
             // the floor is +2 but our check is a <, so only + 1
             largest_y += 1;
         }
+        // sand spreads at most one column left or right for every row it falls
+        let width = board
+            .iter()
+            .map(|p| p.x())
+            .max()
+            .unwrap()
+            .max(SAND_X + largest_y + 1)
+            + 1;
+        let mut grid = Grid::new(width as usize, (largest_y + 1) as usize, Tile::Air);
+        for p in board {
+            grid[p] = Tile::Rock;
+        }
+
         Self {
-            board: HashSet::from_iter(board.into_iter()),
+            board: grid,
             largest_y,
             part2,
             end: Point::new(SAND_X, 0),
+            last_settled: None,
+        }
+    }
+
+    /// The leftmost and rightmost columns with rock or sand in them, or the source
+    fn columns(&self) -> (i32, i32) {
+        self.board
+            .iter()
+            .filter(|(_, &tile)| tile != Tile::Air)
+            .map(|(p, _)| p.x())
+            .chain([SAND_X])
+            .minmax()
+            .into_option()
+            .unwrap()
+    }
+
+    /// The cave cropped to the columns in use, with the sand that settled last in red
+    fn canvas(&self) -> Canvas {
+        let (left, right) = self.columns();
+        let offset = Point::new(left, 0);
+        let mut canvas = Canvas::new((right - left + 1) as usize, self.board.height(), '.');
+        for (p, tile) in self.board.iter() {
+            match tile {
+                Tile::Air => {}
+                Tile::Rock => canvas.put(p - offset, Cell::plain('#')),
+                Tile::Sand => canvas.put(p - offset, Cell::colored('o', Color::Yellow)),
+            }
+        }
+        if self.board[self.end] == Tile::Air {
+            canvas.put(self.end - offset, Cell::plain('+'));
+        }
+        if let Some(sand) = self.last_settled {
+            canvas.put(
+                sand - offset,
+                Cell::colored('o', Color::Red).with_marker('*'),
+            );
+            canvas.set_focus(sand - offset);
         }
+
+        canvas
+            .with_legend(Cell::plain('+'), "source")
+            .with_legend(Cell::plain('#'), "rock")
+            .with_legend(Cell::colored('o', Color::Yellow), "sand")
+            .with_legend(
+                Cell::colored('o', Color::Red).with_marker('*'),
+                "sand that settled last",
+            )
     }
 
     fn drop_sand(&mut self) -> bool {
@@ -38,12 +110,14 @@ impl Board {
                 sand + (1, 1).into(),
             ]
             .into_iter()
-            .find(|p| !self.board.contains(p));
+            .find(|p| self.board[*p] == Tile::Air);
 
             match f {
                 Some(p) => sand = p,
                 None => {
-                    self.board.insert(sand);
+                    self.board[sand] = Tile::Sand;
+                    self.last_settled = Some(sand);
+                    recording::frame(|| self.canvas().render(false));
                     // part2
                     if sand == self.end {
                         return false;
@@ -55,7 +129,9 @@ impl Board {
         }
 
         if self.part2 {
-            self.board.insert(sand);
+            self.board[sand] = Tile::Sand;
+            self.last_settled = Some(sand);
+            recording::frame(|| self.canvas().render(false));
             true
         } else {
             // end of game
@@ -70,7 +146,7 @@ fn read_input(lines: &[String]) -> Vec<Point> {
         .flat_map(|l| {
             let iter = l.split(" -> ").map(|p| {
                 let p = p.split_once(',').unwrap();
-                Point::new(p.0.parse().unwrap(), p.1.parse().unwrap())
+                Point::new(p.0.parse::<i32>().unwrap(), p.1.parse().unwrap())
             });
 
             iter.clone().zip(iter.skip(1)).flat_map(|(p1, p2)| {
@@ -90,26 +166,55 @@ fn read_input(lines: &[String]) -> Vec<Point> {
         .collect()
 }
 
+/// Drops sand until no more comes to rest, outputs the board and how many units did
+fn pour(lines: &[String], part2: bool) -> (Board, usize) {
+    let mut board = Board::new(read_input(lines), part2);
+    let mut resting = 0;
+    while board.drop_sand() {
+        resting += 1;
+    }
+    (board, resting)
+}
+
 pub struct Problem14 {}
 
 impl Problem for Problem14 {
-    fn solve_part1(&mut self, lines: &[String]) -> String {
-        let v = read_input(lines);
-        let mut board = Board::new(v, false);
-        let mut i = 0;
-        while board.drop_sand() {
-            i += 1;
+    fn metadata(&self) -> Metadata {
+        Metadata {
+            title: "Regolith Reservoir",
+            part1: Status::Solved,
+            part2: Status::Solved,
+            tags: &[Tag::Grid, Tag::Simulation],
         }
-        i.to_string()
+    }
+
+    fn solve_part1(&mut self, lines: &[String]) -> String {
+        pour(lines, false).1.to_string()
     }
 
     fn solve_part2(&mut self, lines: &[String]) -> String {
-        let v = read_input(lines);
-        let mut board = Board::new(v, true);
-        let mut i = 0;
-        while board.drop_sand() {
-            i += 1;
-        }
-        (i + 1).to_string()
+        // the unit blocking the source comes to rest too
+        (pour(lines, true).1 + 1).to_string()
+    }
+
+    fn show(&mut self, part: Part, lines: &[String]) -> Option<Canvas> {
+        Some(pour(lines, part == Part::Two).0.canvas())
+    }
+
+    /// The cave once the sand stops, rock in gray and sand in yellow, cropped to the columns
+    /// either of them reach
+    fn render(&mut self, part: Part, lines: &[String]) -> Option<Image> {
+        let (board, _) = pour(lines, part == Part::Two);
+        let (left, right) = board.columns();
+        let width = (right - left + 1) as usize;
+        Some(Image::from_fn(
+            width,
+            board.board.height(),
+            |p| match board.board[p + Point::new(left, 0)] {
+                Tile::Air => Rgb::BLACK,
+                Tile::Rock => Rgb::gray(128),
+                Tile::Sand => Rgb::YELLOW,
+            },
+        ))
     }
 }
@@ -1,71 +1,76 @@
-use std::collections::HashSet;
+use std::{collections::HashSet, ops::RangeInclusive};
 
-use regex;
+use crate::{
+    intervals::IntervalSet,
+    parse::{self, integer, literal, map, pair, parse_lines, preceded, PResult},
+    point::Point,
+    Metadata, Part, Problem, Status, Tag, Variant,
+};
 
-use crate::{point::Point, Problem};
+/// The row part 1 asks about, as the real input states it; the example asks about row 10
+const ROW: i64 = 2_000_000;
+
+/// The largest coordinate part 2 searches up to in the real input; the example stops at 20
+const LIMIT: i64 = 4_000_000;
 
 #[derive(Debug)]
 struct Board {
     sensors: Vec<Sensor>,
-    beacons: HashSet<Point>,
+    beacons: HashSet<Point<i64>>,
 }
 
 impl Board {
-    fn row_coverage(&self, y: i32) -> HashSet<Point> {
-        let covered: HashSet<_> = self
-            .sensors
+    /// The x coordinates on row `y` that can't hold a beacon
+    fn row_coverage(&self, y: i64) -> IntervalSet<i64> {
+        let covered: IntervalSet<_> = self.sensors.iter().map(|s| s.row_reach(y)).collect();
+        let beacons: IntervalSet<_> = self
+            .beacons
             .iter()
-            .flat_map(|s| {
-                // radius - distance to the row = amount of movement left/right
-                let d = (s.location.y() - y).abs() - s.radius;
-                match d.signum() {
-                    // positive result means the sensor is too far away for radius to reach
-                    1 => vec![],
-                    // radius exactly reaches, only one point
-                    0 => vec![Point::new(s.location.x(), y)],
-                    // radius crosses the row, so emit points to the left/right,
-                    // recall d is negative after radius subtraction
-                    -1 => (d..=-d)
-                        .map(|i| Point::new(s.location.x() + i, y))
-                        .collect(),
-                    _ => panic!("uh, signum should be [-1,1]"),
-                }
-            })
+            .filter(|b| b.y() == y)
+            .map(|b| b.x()..=b.x())
             .collect();
-        &covered - &self.beacons
+        covered.difference(&beacons)
     }
 }
 
 #[derive(Debug)]
 struct Sensor {
-    location: Point,
-    radius: i32,
+    location: Point<i64>,
+    radius: i64,
 }
 
 impl Sensor {
-    fn contains(&self, p: &Point) -> bool {
+    /// The x coordinates the sensor's radius reaches on row `y`, empty when it doesn't reach
+    fn row_reach(&self, y: i64) -> RangeInclusive<i64> {
+        // radius - distance to the row = amount of movement left/right
+        let d = self.radius - (self.location.y() - y).abs();
+        (self.location.x() - d)..=(self.location.x() + d)
+    }
+
+    fn contains(&self, p: &Point<i64>) -> bool {
         self.location.manhattan_distance(p) <= self.radius
     }
 }
 
+fn point(input: &str) -> PResult<'_, Point<i64>> {
+    map(
+        pair(
+            preceded(literal("x="), integer()),
+            preceded(literal(", y="), integer()),
+        ),
+        |(x, y)| Point::new(x, y),
+    )(input)
+}
+
 fn read_input(lines: &[String]) -> Board {
-    let re = regex::Regex::new(
-        r#"Sensor at x=(-?\d+), y=(-?\d+): closest beacon is at x=(-?\d+), y=(-?\d+)"#,
-    )
-    .unwrap();
-    let (sensors, beacons) = lines
-        .iter()
-        .map(|l| {
-            let captures = re.captures(l).unwrap();
-
-            let location = Point::new(
-                captures.get(1).unwrap().as_str().parse().unwrap(),
-                captures.get(2).unwrap().as_str().parse().unwrap(),
-            );
-            let beacon = Point::new(
-                captures.get(3).unwrap().as_str().parse().unwrap(),
-                captures.get(4).unwrap().as_str().parse().unwrap(),
-            );
+    let report = pair(
+        preceded(literal("Sensor at "), point),
+        preceded(literal(": closest beacon is at "), point),
+    );
+    let (sensors, beacons) = parse_lines(parse::lines(report), lines)
+        .expect("invalid input")
+        .into_iter()
+        .map(|(location, beacon)| {
             let sensor = Sensor {
                 location,
                 radius: location.manhattan_distance(&beacon),
@@ -80,16 +85,22 @@ fn read_input(lines: &[String]) -> Board {
 
 pub struct Problem15;
 impl Problem for Problem15 {
+    fn metadata(&self) -> Metadata {
+        Metadata {
+            title: "Beacon Exclusion Zone",
+            part1: Status::Solved,
+            part2: Status::Solved,
+            tags: &[Tag::Grid, Tag::Math],
+        }
+    }
+
     fn solve_part1(&mut self, lines: &[String]) -> String {
         let board = read_input(lines);
-        //let coverage_example = board.row_coverage(10).len();
-        let coverage_input = board.row_coverage(2_000_000).len();
-        coverage_input.to_string()
+        board.row_coverage(ROW).total_len().to_string()
     }
 
     fn solve_part2(&mut self, lines: &[String]) -> String {
         // we only need to check the points along the perimeter of each sensor
-        let board_limit = (4_000_000, 4_000_000); // (20, 20)
         let board = read_input(lines);
         let p = board
             .sensors
@@ -106,10 +117,37 @@ impl Problem for Problem15 {
                 })
             })
             .find(|p| {
-                (p.x() >= 0 && p.y() >= 0 && p.x() <= board_limit.0 && p.y() <= board_limit.1)
+                (0..=LIMIT).contains(&p.x())
+                    && (0..=LIMIT).contains(&p.y())
                     && board.sensors.iter().all(|s| !s.contains(p))
             })
             .unwrap();
-        (p.x() as i64 * 4_000_000 + p.y() as i64).to_string()
+        (p.x() * 4_000_000 + p.y()).to_string()
+    }
+
+    fn variants(&self, part: Part) -> Vec<Variant> {
+        match part {
+            Part::One => vec![],
+            Part::Two => vec![Variant {
+                name: "row-scan",
+                solve: row_scan,
+            }],
+        }
     }
 }
+
+/// Checks every row for a gap in the sensors' coverage, one interval per sensor
+fn row_scan(lines: &[String]) -> String {
+    let board = read_input(lines);
+    let (x, y) = (0..=LIMIT)
+        .find_map(|y| {
+            let covered: IntervalSet<_> = board.sensors.iter().map(|s| s.row_reach(y)).collect();
+            covered
+                .gaps(0..=LIMIT)
+                .ranges()
+                .next()
+                .map(|gap| (*gap.start(), y))
+        })
+        .unwrap();
+    (x * 4_000_000 + y).to_string()
+}
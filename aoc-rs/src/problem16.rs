@@ -1,224 +1,302 @@
-use std::{cell::RefCell, collections::HashMap, rc::Rc};
+use std::{cmp::Reverse, collections::HashMap};
 
-use regex;
+use itertools::Itertools;
 
-use crate::Problem;
+use crate::{
+    arena::{Graph, NodeId},
+    bitset::BitSet,
+    distances::DistanceMatrix,
+    optimize::{self, Optimum},
+    parse::{self, either, integer, literal, pair, parse_lines, preceded, separated1, word},
+    Explanation, Metadata, Part, Problem, Status, Tag, Variant,
+};
 
-struct Node<Label, T> {
-    label: Label,
-    item: T,
-    next: RefCell<Vec<Rc<Node<Label, T>>>>,
+struct Valve {
+    name: String,
+    flow_rate: i32,
 }
 
-impl<Label, T> Node<Label, T>
-where
-    Label: PartialEq + Clone,
-{
-    fn new(label: Label, item: T) -> Self {
-        Self {
-            label,
-            item,
-            next: RefCell::new(vec![]),
-        }
-    }
+type Valves = Graph<Valve>;
 
-    fn add_next(&self, next: &Rc<Node<Label, T>>) {
-        self.next.borrow_mut().push(Rc::clone(next));
+fn read_input(lines: &[String]) -> Valves {
+    let valve = pair(
+        preceded(literal("Valve "), word()),
+        pair(
+            preceded(literal(" has flow rate="), integer::<i32>()),
+            preceded(
+                either(
+                    literal("; tunnels lead to valves "),
+                    literal("; tunnel leads to valve "),
+                ),
+                separated1(word(), literal(", ")),
+            ),
+        ),
+    );
+    let valves = parse_lines(parse::lines(valve), lines).expect("invalid input");
+
+    let mut graph = Graph::new();
+    let ids: HashMap<_, _> = valves
+        .iter()
+        .map(|(name, (flow_rate, _))| {
+            let valve = Valve {
+                name: name.clone(),
+                flow_rate: *flow_rate,
+            };
+            (name.clone(), graph.add_node(valve))
+        })
+        .collect();
+    for (name, (_, next_valves)) in &valves {
+        for next in next_valves {
+            graph.add_edge(ids[name], ids[next]);
+        }
     }
+    graph
 }
 
-type PNode = Rc<Node<String, i32>>;
+type Distances = DistanceMatrix<NodeId<Valve>, i32>;
 
-struct DirectedGraph<Label, T> {
-    nodes: HashMap<String, Rc<Node<Label, T>>>,
+/// Valves sorted by name, so the matrix is laid out the same whatever order the input lists them
+fn sorted_valves(graph: &Valves) -> Vec<NodeId<Valve>> {
+    let mut valves: Vec<_> = graph.ids().collect();
+    valves.sort_by(|&a, &b| graph[a].name.cmp(&graph[b].name));
+    valves
 }
 
-fn read_input(lines: &[String]) -> DirectedGraph<String, i32> {
-    let re =
-        regex::Regex::new(r#"Valve (\w+) has flow rate=(\d+); tunnels? leads? to valves? (.*)"#)
-            .unwrap();
-    let (nodes, nexts): (Vec<PNode>, Vec<&str>) = lines
-        .iter()
-        .map(|l| {
-            let captures = re.captures(l).unwrap();
-            let (valve, flow_rate, next_valves) = (
-                captures.get(1).unwrap().as_str().to_owned(),
-                captures.get(2).unwrap().as_str().parse::<i32>().unwrap(),
-                captures.get(3).unwrap().as_str(),
-            );
-
-            let node = Rc::new(Node::new(valve, flow_rate));
-            (Rc::clone(&node), next_valves)
-        })
-        .unzip();
-
-    let node_map: HashMap<_, _> = nodes
-        .iter()
-        .map(|n| (n.label.clone(), Rc::clone(n)))
-        .collect();
+fn tunnels(graph: &Valves) -> impl FnMut(&NodeId<Valve>) -> Vec<NodeId<Valve>> + '_ {
+    |&valve| graph.neighbors(valve).collect()
+}
 
-    nodes
-        .iter()
-        .zip(nexts.into_iter())
-        .for_each(|(node, next_valves)| {
-            next_valves
-                .split(", ")
-                .for_each(|name| node.add_next(node_map.get(name).unwrap()));
-        });
-
-    DirectedGraph { nodes: node_map }
+/// The tunnels all take a minute, so a breadth-first search from every valve is enough
+fn distance_matrix(graph: &Valves) -> Distances {
+    DistanceMatrix::bfs(sorted_valves(graph), tunnels(graph))
 }
 
-fn distance_matrix(graph: &DirectedGraph<String, i32>) -> (HashMap<String, usize>, Vec<Vec<i32>>) {
-    let number_of_nodes = graph.nodes.len();
-    let mut ordered_node_labels = graph.nodes.keys().collect::<Vec<_>>();
-    ordered_node_labels.sort();
+fn floyd_warshall(graph: &Valves) -> Distances {
+    let mut tunnels = tunnels(graph);
+    let edges = sorted_valves(graph)
+        .into_iter()
+        .flat_map(|from| tunnels(&from).into_iter().map(move |to| (from, to, 1)))
+        .collect::<Vec<_>>();
+    DistanceMatrix::floyd_warshall(sorted_valves(graph), edges)
+}
 
-    let mut v: Vec<Vec<i32>> =
-        std::iter::repeat(std::iter::repeat(1000).take(number_of_nodes).collect())
-            .take(number_of_nodes)
-            .collect();
+/// Where everyone starts, "AA", by index in the distance matrix
+fn start_valve(graph: &Valves, matrix: &Distances) -> usize {
+    let start = graph
+        .find(|v| v.name == "AA")
+        .expect("there is no valve AA");
+    matrix
+        .index(&start)
+        .expect("the distance matrix has every valve")
+}
 
-    (0..number_of_nodes).for_each(|i| {
-        v[i][i] = 0;
-    });
+/// Which valve you're at, the time left, and the valves already opened
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+struct Position {
+    valve: usize,
+    time: i32,
+    opened: BitSet,
+}
 
-    ordered_node_labels
-        .iter()
-        .enumerate()
-        .for_each(|(i, &node_label)| {
-            graph
-                .nodes
-                .get(node_label)
-                .unwrap()
-                .next
-                .borrow()
-                .iter()
-                .for_each(|next_node| {
-                    let next_i = ordered_node_labels
-                        .iter()
-                        .position(|&label| label == &next_node.label)
-                        .unwrap();
-                    v[i][next_i] = 1;
-                });
-        });
-
-    // floyd-warshall algorithm
-    for k in 0..number_of_nodes {
-        for i in 0..number_of_nodes {
-            for j in 0..number_of_nodes {
-                v[i][j] = v[i][j].min(v[i][k] + v[k][j]);
-            }
+impl Position {
+    fn start(valve: usize, time: i32, opened: BitSet) -> Self {
+        Self {
+            valve,
+            time,
+            opened,
         }
     }
-
-    (
-        ordered_node_labels
-            .into_iter()
-            .enumerate()
-            .map(|(i, label)| (label.clone(), i))
-            .collect(),
-        v,
-    )
 }
 
+/// The most pressure that can be released from `start`, the path visits a valve per step and
+/// opens it
 fn find_max_pressure(
-    graph: &[Vec<i32>],
+    graph: &Distances,
     viable_valves: &HashMap<usize, i32>,
-    current_valve: usize,
-    time: i32,
-    opened: i64,
-) -> i32 {
-    let mut max_pressure = 0;
-    for (&valve, rate) in viable_valves.iter() {
-        let valve_position = 0x1 << valve;
-        // already opened
-        if opened & valve_position != 0 {
-            continue;
-        }
+    start: Position,
+    memoized: bool,
+) -> Optimum<Position, i32> {
+    // the valves still closed that could be opened in time, with the pressure each would release
+    let openings = |p: &Position| {
+        viable_valves
+            .iter()
+            .filter(|(&valve, _)| !p.opened.contains(valve))
+            .filter_map(|(&valve, &rate)| {
+                let time = p.time - graph.get(p.valve, valve)? - 1;
+                (time > 0).then_some((valve, time, rate * time))
+            })
+            .collect::<Vec<_>>()
+    };
+    // the most pressure first, finding good paths early prunes more
+    let successors = |p: &Position| {
+        openings(p)
+            .into_iter()
+            .sorted_by_key(|&(valve, _, pressure)| (Reverse(pressure), valve))
+            .map(|(valve, time, pressure)| {
+                let next = Position {
+                    valve,
+                    time,
+                    opened: p.opened.with(valve),
+                };
+                (next, pressure)
+            })
+            .collect::<Vec<_>>()
+    };
+    // as if every valve could be reached straight from this one
+    let upper_bound = |p: &Position| openings(p).iter().map(|&(_, _, pressure)| pressure).sum();
 
-        let cost = graph[current_valve][valve] + 1;
-        if time - cost >= 0 {
-            max_pressure = max_pressure.max(
-                rate * (time - cost)
-                    + find_max_pressure(
-                        graph,
-                        viable_valves,
-                        valve,
-                        time - cost,
-                        opened | valve_position,
-                    ),
-            )
-        }
+    match memoized {
+        true => optimize::maximize_memoized(start, successors, upper_bound, |&p| p),
+        false => optimize::maximize(start, successors, upper_bound),
     }
-
-    max_pressure
-}
-
-fn powerset<T>(s: &[T]) -> Vec<Vec<&T>> {
-    (0..2usize.pow(s.len() as u32))
-        .map(|i| {
-            s.iter()
-                .enumerate()
-                .filter(|&(t, _)| (i >> t) % 2 == 1)
-                .map(|(_, element)| element)
-                .collect()
-        })
-        .collect()
 }
 
 pub struct Problem16;
 impl Problem for Problem16 {
+    fn metadata(&self) -> Metadata {
+        Metadata {
+            title: "Proboscidea Volcanium",
+            part1: Status::Solved,
+            part2: Status::Solved,
+            tags: &[Tag::Graph, Tag::Search],
+        }
+    }
+
     fn solve_part1(&mut self, lines: &[String]) -> String {
         let graph = read_input(lines);
-
-        let (label_to_index, matrix) = distance_matrix(&graph);
-        let viable_valves = &label_to_index
-            .iter()
-            .map(|(k, _)| graph.nodes.get(k).unwrap())
-            .filter(|node| node.item > 0)
-            .map(|n| (*label_to_index.get(&n.label).unwrap(), n.item))
-            .collect();
-        find_max_pressure(&matrix, viable_valves, 0, 30, 0).to_string()
+        let matrix = distance_matrix(&graph);
+        find_max_pressure(
+            &matrix,
+            &viable_valves(&graph, &matrix),
+            Position::start(start_valve(&graph, &matrix), 30, BitSet::new()),
+            false,
+        )
+        .value
+        .to_string()
     }
 
     fn solve_part2(&mut self, lines: &[String]) -> String {
         let graph = read_input(lines);
+        let matrix = distance_matrix(&graph);
+        let start = start_valve(&graph, &matrix);
+        best_split(&matrix, &viable_valves(&graph, &matrix), start)
+            .0
+            .to_string()
+    }
 
-        let (label_to_index, matrix) = distance_matrix(&graph);
-        let viable_valves = &label_to_index
-            .iter()
-            .map(|(k, _)| graph.nodes.get(k).unwrap())
-            .filter(|node| node.item > 0)
-            .map(|n| (*label_to_index.get(&n.label).unwrap(), n.item))
-            .collect::<HashMap<_, _>>();
-
-        // finding the other open valves
-        let complement: i64 = (1 << (viable_valves.iter().map(|(v, _)| v).max().unwrap() + 1)) - 1;
-        let valid_valves: i64 = viable_valves.keys().fold(0, |acc, n| 1 << n | acc);
-
-        let mut pressures = HashMap::new();
-        for v in powerset(&viable_valves.iter().collect::<Vec<(_, _)>>()) {
-            let opened = valves_to_bits(&v);
-            pressures.insert(
-                opened,
-                find_max_pressure(&matrix, viable_valves, 0, 26, opened),
-            );
+    fn variants(&self, part: Part) -> Vec<Variant> {
+        match part {
+            Part::One => vec![
+                Variant {
+                    name: "floyd-warshall",
+                    solve: |lines| {
+                        let graph = read_input(lines);
+                        let matrix = floyd_warshall(&graph);
+                        let valves = viable_valves(&graph, &matrix);
+                        find_max_pressure(
+                            &matrix,
+                            &valves,
+                            Position::start(start_valve(&graph, &matrix), 30, BitSet::new()),
+                            false,
+                        )
+                        .value
+                        .to_string()
+                    },
+                },
+                Variant {
+                    name: "memoized",
+                    solve: |lines| {
+                        let graph = read_input(lines);
+                        let matrix = distance_matrix(&graph);
+                        let valves = viable_valves(&graph, &matrix);
+                        find_max_pressure(
+                            &matrix,
+                            &valves,
+                            Position::start(start_valve(&graph, &matrix), 30, BitSet::new()),
+                            true,
+                        )
+                        .value
+                        .to_string()
+                    },
+                },
+            ],
+            Part::Two => vec![],
         }
+    }
 
-        println!("finding best combination");
-        let mut max_pressure = 0;
-        for (valves, pressure) in pressures.iter() {
-            let other_pressure = pressures
-                .get(&((valves ^ complement) & valid_valves))
-                .unwrap();
-            max_pressure = max_pressure.max(pressure + other_pressure)
-        }
+    fn explain(&mut self, part: Part, lines: &[String]) -> Option<Explanation> {
+        let graph = read_input(lines);
+        let matrix = distance_matrix(&graph);
+        let viable_valves = &viable_valves(&graph, &matrix);
+        let start_valve = start_valve(&graph, &matrix);
+
+        let (total_time, schedules) = match part {
+            Part::One => (30, vec![("", BitSet::new())]),
+            Part::Two => {
+                let (_, mine, elephants) = best_split(&matrix, viable_valves, start_valve);
+                (26, vec![("you, ", mine), ("elephant, ", elephants)])
+            }
+        };
 
-        max_pressure.to_string()
+        let mut explanation = Explanation::new();
+        for (who, opened) in schedules {
+            let start = Position::start(start_valve, total_time, opened);
+            let best = find_max_pressure(&matrix, viable_valves, start, false);
+            for &Position { valve, time, .. } in best.path.iter().skip(1) {
+                explanation.add(
+                    format!("{}minute {}", who, total_time - time),
+                    format!(
+                        "open {} (releases {})",
+                        graph[*matrix.label(valve)].name,
+                        viable_valves[&valve] * time
+                    ),
+                );
+            }
+        }
+        Some(explanation)
     }
 }
 
-fn valves_to_bits(valves: &[&(&usize, &i32)]) -> i64 {
-    valves.iter().fold(0, |acc, (&n, _)| (1 << n as i64) | acc)
+/// Valves worth opening, by index in the distance matrix, with their flow rates
+fn viable_valves(graph: &Valves, matrix: &Distances) -> HashMap<usize, i32> {
+    matrix
+        .labels()
+        .iter()
+        .enumerate()
+        .map(|(i, &valve)| (i, graph[valve].flow_rate))
+        .filter(|&(_, rate)| rate > 0)
+        .collect()
+}
+
+/// Splits the valves between you and the elephant, outputs the best total pressure along with
+/// the valves each one should leave alone (i.e. treat as already opened). Both start at
+/// `start_valve`.
+fn best_split(
+    graph: &Distances,
+    viable_valves: &HashMap<usize, i32>,
+    start_valve: usize,
+) -> (i32, BitSet, BitSet) {
+    let universe: BitSet = viable_valves.keys().copied().collect();
+    let pressures: HashMap<_, _> = universe
+        .subsets()
+        .map(|opened| {
+            let start = Position::start(start_valve, 26, opened);
+            (
+                opened,
+                find_max_pressure(graph, viable_valves, start, false).value,
+            )
+        })
+        .collect();
+
+    let mut best = (0, BitSet::new(), BitSet::new());
+    for opened in universe.subsets() {
+        // the elephant opens the valves you leave alone
+        let others = opened.complement(universe);
+        let pressure = pressures[&opened] + pressures[&others];
+        if pressure > best.0 {
+            best = (pressure, opened, others);
+        }
+    }
+
+    best
 }
@@ -1,15 +1,27 @@
-use crate::{point::Point, Problem};
+use crate::{
+    counters,
+    cycles::{self, Cycle},
+    grid::Grid,
+    point::{Direction, Point},
+    recording,
+    terminal::{Canvas, Cell, Color},
+    Metadata, Part, Problem, Status, Tag, Variant,
+};
 
-type Board = [[bool; 7]; 40000];
+type Board = Grid<bool>;
 
-enum Direction {
-    Left,
-    Right,
-    Down,
-}
+const WIDTH: usize = 7;
+const HEIGHT: usize = 40000;
+
+/// When the tower grows past this height the map is shifted down, dropping the bottom rows
+const SHIFT_HEIGHT: i32 = 200;
+const SHIFT_ROWS: i32 = 100;
 
-fn solve(moves: &str, count: i64) -> (i32, Vec<i32>) {
-    let pieces = [
+/// How many rows from the top of the tower a frame shows
+const VIEW_ROWS: i32 = 20;
+
+fn pieces() -> [Vec<Point>; 5] {
+    [
         vec![
             Point::new(0, 0),
             Point::new(1, 0),
@@ -42,57 +54,92 @@ fn solve(moves: &str, count: i64) -> (i32, Vec<i32>) {
             Point::new(0, 1),
             Point::new(1, 1),
         ],
-    ];
+    ]
+}
 
-    let mut moves = moves.chars().cycle();
-    let mut next_piece = 0;
-    let mut map: Board = [[false; 7]; 40000];
-    let mut highest = 0;
-    let mut heights = Vec::new();
+fn read_jets(lines: &[String]) -> Vec<Direction> {
+    lines[0]
+        .chars()
+        .map(|c| Direction::try_from(c).expect("bad character"))
+        .collect()
+}
 
-    for _ in 0..count {
-        //2022 {
-        // place piece
-        let mut piece = pieces[next_piece].clone();
-        next_piece = (next_piece + 1) % pieces.len();
+struct Tower {
+    pieces: [Vec<Point>; 5],
+    jets: Vec<Direction>,
+    next_piece: usize,
+    next_jet: usize,
+    map: Board,
+    highest: i32,
+    /// height of the rows that were shifted off the bottom of the map
+    highest_base: i32,
+    shifting: bool,
+    /// the piece still in the air, empty between drops
+    falling: Vec<Point>,
+}
+
+impl Tower {
+    fn new(jets: Vec<Direction>, shifting: bool) -> Self {
+        Self {
+            pieces: pieces(),
+            jets,
+            next_piece: 0,
+            next_jet: 0,
+            map: Grid::new(WIDTH, HEIGHT, false),
+            highest: 0,
+            highest_base: 0,
+            shifting,
+            falling: vec![],
+        }
+    }
+
+    fn height(&self) -> i64 {
+        (self.highest_base + self.highest) as i64
+    }
 
+    /// Puts the next piece in the air, two units from the left wall and three above the tower
+    fn spawn_piece(&mut self) {
+        let mut piece = self.pieces[self.next_piece].clone();
+        self.next_piece = (self.next_piece + 1) % self.pieces.len();
         piece.iter_mut().for_each(|p| {
-            p.shift(2, highest + 3);
+            p.shift(2, self.highest + 3);
         });
+        self.falling = piece;
+    }
+
+    /// Drops the next piece, running jets until it comes to rest
+    fn drop_piece(&mut self) {
+        self.spawn_piece();
+        counters::increment("pieces simulated");
+        recording::frame(|| self.canvas(VIEW_ROWS).render(false));
 
         // run moves until piece sets
         loop {
-            match moves.next().unwrap() {
-                '<' => {
-                    if can_move(&map, &piece, Direction::Left) {
-                        piece.iter_mut().for_each(|p| p.shift(-1, 0))
-                    }
-                }
-                '>' => {
-                    if can_move(&map, &piece, Direction::Right) {
-                        piece.iter_mut().for_each(|p| p.shift(1, 0))
-                    }
-                }
-                _ => panic!("bad character"),
+            counters::increment("jets applied");
+            let jet = self.jets[self.next_jet];
+            self.next_jet = (self.next_jet + 1) % self.jets.len();
+            if can_move(&self.map, &self.falling, jet) {
+                self.falling.iter_mut().for_each(|p| *p += jet.unit());
             }
 
-            match can_move(&map, &piece, Direction::Down) {
-                true => piece.iter_mut().for_each(|p| p.shift(0, -1)),
+            match can_move(&self.map, &self.falling, Direction::Down) {
+                true => {
+                    self.falling
+                        .iter_mut()
+                        .for_each(|p| *p += Direction::Down.unit());
+                    recording::frame(|| self.canvas(VIEW_ROWS).render(false));
+                }
                 false => {
-                    for p in piece.iter() {
-                        map[p.y() as usize][p.x() as usize] = true;
-                        highest = highest.max(p.y() + 1);
+                    for p in std::mem::take(&mut self.falling) {
+                        self.map[p] = true;
+                        self.highest = self.highest.max(p.y() + 1);
                     }
 
-                    heights.push(highest);
-
-                    /*
-                    if highest > 6000 {
-                        shift_map(&mut map, 1000);
-                        highest -= 1000;
-                        highest_base += 1000;
+                    if self.shifting && self.highest > SHIFT_HEIGHT {
+                        shift_map(&mut self.map, SHIFT_ROWS as usize);
+                        self.highest -= SHIFT_ROWS;
+                        self.highest_base += SHIFT_ROWS;
                     }
-                    */
 
                     break;
                 }
@@ -100,96 +147,165 @@ fn solve(moves: &str, count: i64) -> (i32, Vec<i32>) {
         }
     }
 
-    (highest, heights)
-}
+    /// The top `rows` rows of the tower and the space above it, between the walls, with the
+    /// floor once it's in view and the falling piece in red
+    fn canvas(&self, rows: i32) -> Canvas {
+        let top = self
+            .falling
+            .iter()
+            .map(|p| p.y())
+            .fold(self.highest + 2, i32::max);
+        let bottom = (self.highest - rows).max(0);
+        let floor = bottom == 0 && self.highest_base == 0;
+        let height = (top - bottom + 1) as usize + floor as usize;
+        let mut canvas = Canvas::new(WIDTH + 2, height, '.');
+        // up is +y in the map and down on the canvas
+        let to_canvas = |p: Point| Point::new(p.x() + 1, top - p.y());
 
-fn can_move(map: &Board, piece: &[Point], direction: Direction) -> bool {
-    match direction {
-        Direction::Left => piece.iter().all(|p| {
-            let moved = *p - Point::new(1, 0);
-            moved.x() >= 0 && !map[moved.y() as usize][moved.x() as usize]
-        }),
-        Direction::Right => piece.iter().all(|p| {
-            let moved = *p + Point::new(1, 0);
-            moved.x() < 7 && !map[moved.y() as usize][moved.x() as usize]
-        }),
-        Direction::Down => piece.iter().all(|p| {
-            let moved = *p - Point::new(0, 1);
-            moved.y() >= 0 && !map[moved.y() as usize][moved.x() as usize]
-        }),
+        for y in bottom..=top {
+            canvas.put(to_canvas(Point::new(-1, y)), Cell::plain('|'));
+            canvas.put(to_canvas(Point::new(WIDTH as i32, y)), Cell::plain('|'));
+            for x in 0..WIDTH as i32 {
+                if self.map[Point::new(x, y)] {
+                    canvas.put(to_canvas(Point::new(x, y)), Cell::plain('#'));
+                }
+            }
+        }
+        if floor {
+            for x in -1..=WIDTH as i32 {
+                let edge = x == -1 || x == WIDTH as i32;
+                let symbol = if edge { '+' } else { '-' };
+                canvas.put(to_canvas(Point::new(x, -1)), Cell::plain(symbol));
+            }
+        }
+        for &p in &self.falling {
+            canvas.put(to_canvas(p), Cell::colored('@', Color::Red));
+        }
+        canvas.set_focus(Point::new(0, 0));
+
+        canvas
+            .with_legend(Cell::plain('#'), "rock")
+            .with_legend(Cell::colored('@', Color::Red), "the falling rock")
     }
-}
 
-#[allow(dead_code)]
-fn print_map(map: &Board, piece: Option<&[Point]>, highest: usize) {
-    for (y, row) in map.iter().enumerate().take(highest + 1).rev() {
-        for (x, c) in row.iter().enumerate() {
-            if piece.map_or(false, |p| {
-                p.iter().any(|p| p.y() == y as i32 && p.x() == x as i32)
-            }) {
-                print!("@");
-            } else {
-                print!(
-                    "{}",
-                    match c {
-                        true => '#',
-                        false => '.',
-                    }
-                );
+    /// What decides how the tower grows from here: the next piece and jet, and the empty cells a
+    /// falling piece could still get to, as one bit per column for each row down from the top
+    fn key(&self) -> (usize, usize, Vec<u8>) {
+        // pieces only move down and sideways, so each row is reached from the one above it
+        let mut rows = vec![];
+        let mut reachable: u8 = (1 << WIDTH) - 1;
+        for y in (0..self.highest).rev() {
+            let open = (0..WIDTH)
+                .filter(|&x| !self.map[Point::new(x as i32, y)])
+                .fold(0u8, |open, x| open | 1 << x);
+            let mut row = reachable & open;
+            // spread sideways through the open cells
+            loop {
+                let spread = (row | row << 1 | row >> 1) & open;
+                if spread == row {
+                    break;
+                }
+                row = spread;
             }
+            if row == 0 {
+                break;
+            }
+            rows.push(row);
+            reachable = row;
         }
-        println!();
+        (self.next_piece, self.next_jet, rows)
+    }
+}
+
+fn build_tower(lines: &[String], count: usize, shifting: bool) -> Tower {
+    let mut tower = Tower::new(read_jets(lines), shifting);
+    for _ in 0..count {
+        tower.drop_piece();
+    }
+    tower
+}
+
+fn tower_height(lines: &[String], count: usize, shifting: bool) -> i64 {
+    build_tower(lines, count, shifting).height()
+}
+
+/// Drops pieces until the tower starts repeating or `count` pieces have fallen, outputs the
+/// tower at that point, the cycle if there was one, and the height after every piece so far
+fn find_cycle(lines: &[String], count: u64) -> (Tower, Option<Cycle>, Vec<i64>) {
+    // once the next piece, jet and top of the tower repeat, so does the growth in between
+    let mut tower = Tower::new(read_jets(lines), true);
+    let (cycle, heights) = cycles::run(
+        &mut tower,
+        usize::try_from(count).unwrap_or(usize::MAX),
+        Tower::drop_piece,
+        Tower::key,
+        Tower::height,
+    );
+    (tower, cycle, heights)
+}
+
+/// The height after `count` pieces, extrapolated from the cycle, or simulated all the way when
+/// the tower never repeats
+fn extrapolated_height(lines: &[String], count: u64) -> i64 {
+    let (_, cycle, heights) = find_cycle(lines, count);
+    match cycle {
+        Some(cycle) => cycle.extrapolate(&heights, count),
+        None => *heights.last().unwrap(),
     }
-    println!();
+}
+
+/// Moves every row of the map down by `n`, forgetting the bottom `n` rows
+fn shift_map(map: &mut Board, n: usize) {
+    let cells = map.as_mut_slice();
+    cells.copy_within((n * WIDTH).., 0);
+    let l = cells.len();
+    cells[(l - n * WIDTH)..].fill(false);
+}
+
+fn can_move(map: &Board, piece: &[Point], direction: Direction) -> bool {
+    // the walls and floor are outside the map
+    piece
+        .iter()
+        .all(|p| map.get(*p + direction.unit()) == Some(&false))
 }
 
 pub struct Problem17;
 impl Problem for Problem17 {
+    fn metadata(&self) -> Metadata {
+        Metadata {
+            title: "Pyroclastic Flow",
+            part1: Status::Solved,
+            part2: Status::Solved,
+            tags: &[Tag::Grid, Tag::Simulation],
+        }
+    }
+
     fn solve_part1(&mut self, lines: &[String]) -> String {
-        solve(lines[0].as_str(), 2022).0.to_string()
+        tower_height(lines, 2022, false).to_string()
     }
 
     fn solve_part2(&mut self, lines: &[String]) -> String {
-        let n = 20000;
-        let heights = solve(lines[0].as_str(), n).1;
-        let diffs = heights
-            .iter()
-            .zip(heights.iter().skip(1))
-            .map(|(one, two)| two - one)
-            .collect::<Vec<_>>();
-
-        for start in 1..heights.len() {
-            for period_size in 3..5000 {
-                let p1_end = start + period_size;
-                let p2_end = p1_end + period_size;
-                let p3_end = p2_end + period_size;
-
-                if p2_end > diffs.len() {
-                    panic!("nothing found");
-                }
-
-                let p1 = &diffs[start..p1_end];
-                let p2 = &diffs[p1_end..p2_end];
-                let p3 = &diffs[p2_end..p3_end];
-                if p1 == p2 && p2 == p3 {
-                    let start_height = diffs[..start].iter().sum::<i32>() as i64;
-                    let period_height = p1.iter().sum::<i32>() as i64;
-
-                    let turns = 1_000_000_000_000_i64 - start as i64;
-                    let periods = turns / period_size as i64;
-                    let remaining = turns % period_size as i64;
+        extrapolated_height(lines, 1_000_000_000_000).to_string()
+    }
 
-                    let height = start_height
-                        + periods * period_height
-                        + diffs[p1_end..(p1_end + remaining as usize)]
-                            .iter()
-                            .sum::<i32>() as i64;
+    /// The whole tower, for part 2 as it was when it started repeating, with the next piece
+    /// about to fall
+    fn show(&mut self, part: Part, lines: &[String]) -> Option<Canvas> {
+        let mut tower = match part {
+            Part::One => build_tower(lines, 2022, false),
+            Part::Two => find_cycle(lines, 1_000_000_000_000).0,
+        };
+        tower.spawn_piece();
+        Some(tower.canvas(tower.highest))
+    }
 
-                    return height.to_string();
-                }
-            }
+    fn variants(&self, part: Part) -> Vec<Variant> {
+        match part {
+            Part::One => vec![Variant {
+                name: "shifting",
+                solve: |lines| tower_height(lines, 2022, true).to_string(),
+            }],
+            Part::Two => vec![],
         }
-
-        "".to_string()
     }
 }
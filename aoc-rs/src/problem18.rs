@@ -1,8 +1,6 @@
-use std::collections::HashSet;
+use crate::{point::Point3D, voxels::VoxelSet, Metadata, Problem, Status, Tag};
 
-use crate::{point::Point3D, Problem};
-
-fn read_input(lines: &[String]) -> HashSet<Point3D> {
+fn read_input(lines: &[String]) -> VoxelSet {
     lines
         .iter()
         .map(|l| {
@@ -18,67 +16,20 @@ fn read_input(lines: &[String]) -> HashSet<Point3D> {
 
 pub struct Problem18;
 impl Problem for Problem18 {
-    fn solve_part1(&mut self, lines: &[String]) -> String {
-        let cubes = read_input(lines);
-        let directions = [
-            Point3D::new(1, 0, 0),
-            Point3D::new(-1, 0, 0),
-            Point3D::new(0, 1, 0),
-            Point3D::new(0, -1, 0),
-            Point3D::new(0, 0, 1),
-            Point3D::new(0, 0, -1),
-        ];
-        let mut sides_exposed = 0;
-        for c in cubes.iter() {
-            sides_exposed += directions
-                .iter()
-                .filter(|d| !cubes.contains(&(*c + **d)))
-                .count();
+    fn metadata(&self) -> Metadata {
+        Metadata {
+            title: "Boiling Boulders",
+            part1: Status::Solved,
+            part2: Status::Solved,
+            tags: &[Tag::Grid, Tag::Search],
         }
-        sides_exposed.to_string()
-    }
-
-    fn solve_part2(&mut self, lines: &[String]) -> String {
-        let cubes = read_input(lines);
-        crawl(Point3D::new(0, 0, 0), &cubes, &mut HashSet::new()).to_string()
-    }
-}
-
-fn crawl(point: Point3D, cubes: &HashSet<Point3D>, visited: &mut HashSet<Point3D>) -> i32 {
-    // out of bounds
-    if point.x() < -1
-        || point.y() < -1
-        || point.z() < -1
-        || point.x() > 20
-        || point.y() > 20
-        || point.z() > 20
-    {
-        return 0;
-    }
-
-    // we touched something
-    if cubes.contains(&point) {
-        return 1;
     }
 
-    if visited.contains(&point) {
-        return 0;
+    fn solve_part1(&mut self, lines: &[String]) -> String {
+        read_input(lines).surface_area().to_string()
     }
 
-    visited.insert(point);
-
-    let directions = [
-        Point3D::new(-1, 0, 0),
-        Point3D::new(1, 0, 0),
-        Point3D::new(0, -1, 0),
-        Point3D::new(0, 1, 0),
-        Point3D::new(0, 0, -1),
-        Point3D::new(0, 0, 1),
-    ];
-
-    let mut sides_exposed = 0;
-    for d in directions.into_iter() {
-        sides_exposed += crawl(point + d, cubes, visited);
+    fn solve_part2(&mut self, lines: &[String]) -> String {
+        read_input(lines).exterior_surface_area().to_string()
     }
-    sides_exposed
 }
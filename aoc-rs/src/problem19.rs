@@ -1,69 +1,218 @@
-use regex;
+use itertools::Itertools;
 
-use crate::Problem;
+use crate::{
+    optimize::{self, Optimum},
+    parse::{self, delimited, integer, literal, pair, parse_lines, preceded, PResult, Parser},
+    Explanation, Metadata, Part, Problem, Status, Tag,
+};
 
 #[derive(Debug, Copy, Clone)]
 struct Blueprint {
+    id: usize,
     ore_robot: usize,
     clay_robot: usize,
     obsidian_robot: (usize, usize),
     geode_robot: (usize, usize),
 }
 
+/// `<robot>` then `<n> ore.`
+fn ore_cost(robot: &'static str) -> impl Parser<usize> {
+    delimited(literal(robot), integer(), literal(" ore."))
+}
+
+/// `<robot>` then `<n> ore and <m>` then `<other>`
+fn ore_and_cost(robot: &'static str, other: &'static str) -> impl Parser<(usize, usize)> {
+    pair(
+        preceded(literal(robot), integer()),
+        delimited(literal(" ore and "), integer(), literal(other)),
+    )
+}
+
+fn blueprint(input: &str) -> PResult<'_, Blueprint> {
+    let (id, input) = delimited(literal("Blueprint "), integer(), literal(":"))(input)?;
+    let (ore_robot, input) = ore_cost(" Each ore robot costs ")(input)?;
+    let (clay_robot, input) = ore_cost(" Each clay robot costs ")(input)?;
+    let (obsidian_robot, input) = ore_and_cost(" Each obsidian robot costs ", " clay.")(input)?;
+    let (geode_robot, input) = ore_and_cost(" Each geode robot costs ", " obsidian.")(input)?;
+
+    Ok((
+        Blueprint {
+            id,
+            ore_robot,
+            clay_robot,
+            obsidian_robot,
+            geode_robot,
+        },
+        input,
+    ))
+}
+
 fn read_input(lines: &[String]) -> Vec<Blueprint> {
-    let re = regex::Regex::new(r#"costs (\d+) ore(?: and (\d+) \w+)?."#).unwrap();
-
-    lines
-        .iter()
-        .map(|l| {
-            let mut captures = re.captures_iter(l);
-
-            let ore_robot = captures
-                .next()
-                .unwrap()
-                .get(1)
-                .unwrap()
-                .as_str()
-                .parse()
-                .unwrap();
-            let clay_robot = captures
-                .next()
-                .unwrap()
-                .get(1)
-                .unwrap()
-                .as_str()
-                .parse()
-                .unwrap();
-
-            let next = captures.next().unwrap();
-            let obsidian_robot = (
-                next.get(1).unwrap().as_str().parse().unwrap(),
-                next.get(2).unwrap().as_str().parse().unwrap(),
-            );
-            let next = captures.next().unwrap();
-            let geode_robot = (
-                next.get(1).unwrap().as_str().parse().unwrap(),
-                next.get(2).unwrap().as_str().parse().unwrap(),
-            );
+    parse_lines(parse::lines(blueprint), lines).expect("invalid input")
+}
+
+const ORE: usize = 0;
+const CLAY: usize = 1;
+const OBSIDIAN: usize = 2;
+const GEODE: usize = 3;
+
+/// The time left, and the robots and resources of every kind but geodes. Geodes are counted as
+/// soon as their robot is built, for every minute it has left to crack them.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+struct Factory {
+    time: usize,
+    robots: [usize; 3],
+    resources: [usize; 3],
+}
+
+impl Blueprint {
+    /// The ore, clay and obsidian each kind of robot costs
+    fn costs(&self) -> [[usize; 3]; 4] {
+        [
+            [self.ore_robot, 0, 0],
+            [self.clay_robot, 0, 0],
+            [self.obsidian_robot.0, self.obsidian_robot.1, 0],
+            [self.geode_robot.0, 0, self.geode_robot.1],
+        ]
+    }
+
+    /// The factory after waiting for `robot` to be affordable and building it, with the geodes
+    /// that gains. `None` if it can't be built with time to spare.
+    fn build(&self, factory: &Factory, robot: usize) -> Option<(Factory, usize)> {
+        let cost = self.costs()[robot];
+        let mut wait = 0;
+        for kind in [ORE, CLAY, OBSIDIAN] {
+            let missing = cost[kind].saturating_sub(factory.resources[kind]);
+            if missing > 0 {
+                if factory.robots[kind] == 0 {
+                    return None;
+                }
+                wait = wait.max(missing.div_ceil(factory.robots[kind]));
+            }
+        }
+
+        // the robot takes a minute to build, and needs another to do anything
+        let elapsed = wait + 1;
+        if elapsed >= factory.time {
+            return None;
+        }
 
-            Blueprint {
-                ore_robot,
-                clay_robot,
-                obsidian_robot,
-                geode_robot,
+        let mut next = *factory;
+        next.time -= elapsed;
+        for kind in [ORE, CLAY, OBSIDIAN] {
+            next.resources[kind] =
+                factory.resources[kind] + factory.robots[kind] * elapsed - cost[kind];
+        }
+        match robot {
+            GEODE => Some((next, next.time)),
+            _ => {
+                next.robots[robot] += 1;
+                Some((next, 0))
             }
-        })
-        .collect()
+        }
+    }
+
+    /// The factories that can follow this one by building each kind of robot next, geodes
+    /// first. There's no point having more robots of a kind than can be spent in a minute.
+    fn successors(&self, factory: &Factory) -> Vec<(Factory, usize)> {
+        let costs = self.costs();
+        [GEODE, OBSIDIAN, CLAY, ORE]
+            .into_iter()
+            .filter(|&robot| {
+                robot == GEODE
+                    || factory.robots[robot] < costs.iter().map(|c| c[robot]).max().unwrap()
+            })
+            .filter_map(|robot| self.build(factory, robot))
+            .collect()
+    }
+
+    /// The geodes that could still be cracked if ore were free and any number of robots could be
+    /// built each minute
+    fn upper_bound(&self, factory: &Factory) -> usize {
+        let [_, mut clay, mut obsidian] = factory.resources;
+        let [_, mut clay_robots, mut obsidian_robots] = factory.robots;
+        let mut geodes = 0;
+        for time in (1..=factory.time).rev() {
+            if obsidian >= self.geode_robot.1 {
+                obsidian -= self.geode_robot.1;
+                geodes += time - 1;
+            }
+            let obsidian_robot = clay >= self.obsidian_robot.1;
+            if obsidian_robot {
+                clay -= self.obsidian_robot.1;
+            }
+
+            clay += clay_robots;
+            obsidian += obsidian_robots;
+            clay_robots += 1;
+            if obsidian_robot {
+                obsidian_robots += 1;
+            }
+        }
+        geodes
+    }
+
+    fn max_geodes(&self, time: usize) -> Optimum<Factory, usize> {
+        let start = Factory {
+            time,
+            robots: [1, 0, 0],
+            resources: [0, 0, 0],
+        };
+        optimize::maximize(start, |f| self.successors(f), |f| self.upper_bound(f))
+    }
 }
 
 pub struct Problem19;
 impl Problem for Problem19 {
+    fn metadata(&self) -> Metadata {
+        Metadata {
+            title: "Not Enough Minerals",
+            part1: Status::Solved,
+            part2: Status::Solved,
+            tags: &[Tag::Parsing, Tag::Search],
+        }
+    }
+
     fn solve_part1(&mut self, lines: &[String]) -> String {
-        let blueprints = read_input(lines);
-        "".to_string()
+        read_input(lines)
+            .iter()
+            .map(|b| b.id * b.max_geodes(24).value)
+            .sum::<usize>()
+            .to_string()
     }
 
-    fn solve_part2(&mut self, _lines: &[String]) -> String {
-        todo!()
+    fn solve_part2(&mut self, lines: &[String]) -> String {
+        read_input(lines)
+            .iter()
+            .take(3)
+            .map(|b| b.max_geodes(32).value)
+            .product::<usize>()
+            .to_string()
+    }
+
+    fn explain(&mut self, part: Part, lines: &[String]) -> Option<Explanation> {
+        let (time, count) = match part {
+            Part::One => (24, usize::MAX),
+            Part::Two => (32, 3),
+        };
+
+        let mut explanation = Explanation::new();
+        for blueprint in read_input(lines).iter().take(count) {
+            let best = blueprint.max_geodes(time);
+            let geode_robots = best
+                .path
+                .windows(2)
+                .filter(|w| w[0].robots == w[1].robots)
+                .map(|w| time - w[1].time)
+                .join(", ");
+            explanation.add(
+                format!("blueprint {}", blueprint.id),
+                format!(
+                    "{} geodes (geode robots built in minutes {})",
+                    best.value, geode_robots
+                ),
+            );
+        }
+        Some(explanation)
     }
 }
@@ -1,6 +1,6 @@
 use num_enum::{IntoPrimitive, TryFromPrimitive};
 
-use crate::Problem;
+use crate::{Metadata, Problem, Status, Tag};
 
 #[derive(IntoPrimitive, TryFromPrimitive, Debug, Clone, Copy)]
 #[repr(u8)]
@@ -55,6 +55,15 @@ impl Choice {
 pub struct Problem2;
 
 impl Problem for Problem2 {
+    fn metadata(&self) -> Metadata {
+        Metadata {
+            title: "Rock Paper Scissors",
+            part1: Status::Solved,
+            part2: Status::Solved,
+            tags: &[Tag::Parsing],
+        }
+    }
+
     fn solve_part1(&mut self, lines: &[String]) -> String {
         lines
             .iter()
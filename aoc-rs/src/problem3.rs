@@ -2,7 +2,7 @@ use std::collections::HashSet;
 
 use itertools::Itertools;
 
-use crate::Problem;
+use crate::{Metadata, Problem, Status, Tag};
 
 fn score(c: char) -> i64 {
     (if c.is_lowercase() {
@@ -14,6 +14,15 @@ fn score(c: char) -> i64 {
 
 pub struct Problem3;
 impl Problem for Problem3 {
+    fn metadata(&self) -> Metadata {
+        Metadata {
+            title: "Rucksack Reorganization",
+            part1: Status::Solved,
+            part2: Status::Solved,
+            tags: &[Tag::Parsing],
+        }
+    }
+
     fn solve_part1(&mut self, lines: &[String]) -> String {
         lines
             .iter()
@@ -22,7 +31,6 @@ impl Problem for Problem3 {
                 *one.chars()
                     .collect::<HashSet<_>>()
                     .intersection(&two.chars().collect::<HashSet<_>>())
-                    .into_iter()
                     .next()
                     .unwrap()
             })
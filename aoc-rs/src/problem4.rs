@@ -1,35 +1,46 @@
-use crate::Problem;
+use crate::{
+    intervals::IntervalSet,
+    parse::{self, integer, literal, map, pair, parse_lines, preceded, PResult},
+    Metadata, Problem, Status, Tag,
+};
+
+/// `<start>-<end>`
+fn sections(input: &str) -> PResult<'_, IntervalSet<i64>> {
+    map(
+        pair(integer(), preceded(literal("-"), integer())),
+        |(start, end)| IntervalSet::from(start..=end),
+    )(input)
+}
+
+/// The sections assigned to each pair of elves
+fn read_input(lines: &[String]) -> Vec<(IntervalSet<i64>, IntervalSet<i64>)> {
+    let elves = pair(sections, preceded(literal(","), sections));
+    parse_lines(parse::lines(elves), lines).expect("invalid input")
+}
 
 pub struct Problem4;
 impl Problem for Problem4 {
+    fn metadata(&self) -> Metadata {
+        Metadata {
+            title: "Camp Cleanup",
+            part1: Status::Solved,
+            part2: Status::Solved,
+            tags: &[Tag::Parsing],
+        }
+    }
+
     fn solve_part1(&mut self, lines: &[String]) -> String {
-        lines
-            .iter()
-            .map(|l| l.split_once(',').unwrap())
-            .map(|(one, two)| (one.split_once('-').unwrap(), two.split_once('-').unwrap()))
-            .filter(|((s1, e1), (s2, e2))| {
-                let e = (e2.parse::<i64>().unwrap() - e1.parse::<i64>().unwrap()).signum();
-                let s = (s2.parse::<i64>().unwrap() - s1.parse::<i64>().unwrap()).signum();
-                // when 0, it's always contained, otherwise the difference between start and end
-                // needs to be opposite sign
-                matches!((s, e), (0, _) | (_, 0) | (-1, 1) | (1, -1))
-            })
+        read_input(lines)
+            .into_iter()
+            .filter(|(one, two)| one.is_superset(two) || two.is_superset(one))
             .count()
             .to_string()
     }
 
     fn solve_part2(&mut self, lines: &[String]) -> String {
-        lines
-            .iter()
-            .map(|l| l.split_once(',').unwrap())
-            .map(|(one, two)| (one.split_once('-').unwrap(), two.split_once('-').unwrap()))
-            .filter(|((s1, e1), (s2, e2))| {
-                let e1 = e1.parse::<i64>().unwrap();
-                let e2 = e2.parse::<i64>().unwrap();
-                let s1 = s1.parse::<i64>().unwrap();
-                let s2 = s2.parse::<i64>().unwrap();
-                s1 <= e2 && e1 >= s2
-            })
+        read_input(lines)
+            .into_iter()
+            .filter(|(one, two)| !one.intersection(two).is_empty())
             .count()
             .to_string()
     }
@@ -1,56 +1,80 @@
-use itertools::Itertools;
+use crate::{
+    grid::{Grid, Transform},
+    parse::{
+        self, delimited, either, integer, literal, map, parse_lines, preceded, satisfy, separated1,
+        spaces, terminated, value, PResult,
+    },
+    Metadata, Problem, Status, Tag,
+};
 
-use crate::{transpose, Problem};
+type Instruction = (usize, usize, usize);
+
+/// One line of the drawing, left to right
+type Row = Vec<Option<char>>;
+
+/// `[X]` for a crate or three spaces for none
+fn slot(input: &str) -> PResult<'_, Option<char>> {
+    either(
+        map(
+            delimited(
+                literal("["),
+                satisfy(|c| c.is_ascii_uppercase(), "a crate"),
+                literal("]"),
+            ),
+            Some,
+        ),
+        value(literal("   "), None),
+    )(input)
+}
+
+/// `move <n> from <stack> to <stack>`
+fn instruction(input: &str) -> PResult<'_, Instruction> {
+    let (count, input) = preceded(literal("move "), integer())(input)?;
+    let (from, input) = preceded(literal(" from "), integer())(input)?;
+    let (to, input) = preceded(literal(" to "), integer())(input)?;
+    Ok(((count, from, to), input))
+}
+
+/// The drawing of the stacks, their labels, then the instructions
+fn drawing(input: &str) -> PResult<'_, (Vec<Row>, Vec<Instruction>)> {
+    let (rows, input) =
+        terminated(parse::lines(separated1(slot, literal(" "))), literal("\n"))(input)?;
+    let labels = delimited(spaces(), separated1(integer::<usize>(), spaces()), spaces());
+    let (_, input) = terminated(labels, literal("\n\n"))(input)?;
+    let (instructions, input) = parse::lines(instruction)(input)?;
+    Ok(((rows, instructions), input))
+}
 
 pub struct Problem5;
 impl Problem5 {
-    fn read_stacks(&self, lines: &[String]) -> Vec<Vec<char>> {
-        // can't use iterator because we need `rev()`, DoubleEndedIterator
-        #[allow(clippy::needless_collect)]
-        let s: Vec<Vec<char>> = lines
-            .iter()
-            .take_while(|l| !l.is_empty())
-            .map(|l| l.chars().collect())
-            .collect();
+    fn read_input(&self, lines: &[String]) -> (Vec<Vec<char>>, Vec<Instruction>) {
+        let (rows, instructions) = parse_lines(drawing, lines).expect("invalid input");
 
-        transpose(s.into_iter().rev().collect())
-            .into_iter()
-            // skip the left-most column
-            .skip(1)
-            // contents are in every 4th column, including 0 (because we skip the useless column above)
-            .step_by(4)
-            // skip the column numeric label and remove empty entries
-            .map(|row| row.into_iter().skip(1).filter(|&c| c != ' ').collect())
-            .collect()
-    }
+        // lines with no crates on the right may be cut short, and turning the drawing clockwise
+        // lists each stack from the bottom up
+        let drawing = Grid::from_ragged(rows, None);
+        let stacks = drawing
+            .view(Transform::RotateCw)
+            .rows()
+            .map(|stack| stack.flatten().copied().collect())
+            .collect();
 
-    fn read_instructions(&self, lines: &[String]) -> Vec<(usize, usize, usize)> {
-        lines
-            .iter()
-            // skip stacks at beginning of input
-            .skip_while(|l| !l.is_empty())
-            // skip blank line
-            .skip(1)
-            .map(|l| {
-                // split line into words
-                l.split(' ')
-                    // the first word is 'move'
-                    .skip(1)
-                    // take the numbers in the instruction
-                    .step_by(2)
-                    .map(|s| s.parse::<usize>().unwrap())
-                    // turn them into a tuple of (move, from, to)
-                    .collect_tuple()
-                    .unwrap()
-            })
-            .collect()
+        (stacks, instructions)
     }
 }
 
 impl Problem for Problem5 {
+    fn metadata(&self) -> Metadata {
+        Metadata {
+            title: "Supply Stacks",
+            part1: Status::Solved,
+            part2: Status::Solved,
+            tags: &[Tag::Parsing, Tag::Simulation],
+        }
+    }
+
     fn solve_part1(&mut self, lines: &[String]) -> String {
-        let mut stacks = self.read_stacks(lines);
-        let instructions = self.read_instructions(lines);
+        let (mut stacks, instructions) = self.read_input(lines);
 
         for (m, f, t) in instructions.into_iter() {
             for _ in 0..m {
@@ -63,8 +87,7 @@ impl Problem for Problem5 {
     }
 
     fn solve_part2(&mut self, lines: &[String]) -> String {
-        let mut stacks = self.read_stacks(lines);
-        let instructions = self.read_instructions(lines);
+        let (mut stacks, instructions) = self.read_input(lines);
 
         for (m, f, t) in instructions.into_iter() {
             let l = stacks[f - 1].len();
@@ -1,35 +1,61 @@
-use std::collections::HashSet;
-
 use itertools::Itertools;
 
-use crate::Problem;
+use crate::{
+    window::{distinct_runs, DistinctWindow},
+    Explanation, Metadata, Part, Problem, Status, Tag,
+};
+
+/// How many characters have been received after each run of `n` different ones, i.e. every
+/// place a marker ends
+fn markers(line: &str, n: usize) -> impl Iterator<Item = usize> + '_ {
+    distinct_runs(line.bytes(), DistinctWindow::bytes(n))
+}
+
+fn marker_size(part: Part) -> usize {
+    match part {
+        Part::One => 4,
+        Part::Two => 14,
+    }
+}
 
 pub struct Problem6;
 impl Problem6 {
     fn find_packet(&self, lines: &[String], n: usize) -> String {
         lines
             .iter()
-            .map(|s| s.chars().collect::<Vec<_>>())
-            .map(|s| {
-                // walk window of n characters
-                (s.windows(n)
-                    // if unique length is not n, keep walking
-                    .take_while(|w| HashSet::<_>::from_iter(w.iter()).len() != n)
-                    // count the number of windows we saw
-                    .count()
-                    + n) // add n because of the window size
-                    .to_string()
-            })
+            .map(|l| markers(l, n).next().expect("no marker").to_string())
             .join("\n")
     }
 }
 
 impl Problem for Problem6 {
+    fn metadata(&self) -> Metadata {
+        Metadata {
+            title: "Tuning Trouble",
+            part1: Status::Solved,
+            part2: Status::Solved,
+            tags: &[Tag::Parsing],
+        }
+    }
+
     fn solve_part1(&mut self, lines: &[String]) -> String {
-        self.find_packet(lines, 4)
+        self.find_packet(lines, marker_size(Part::One))
     }
 
     fn solve_part2(&mut self, lines: &[String]) -> String {
-        self.find_packet(lines, 14)
+        self.find_packet(lines, marker_size(Part::Two))
+    }
+
+    /// Every marker in each line, the first one being the answer
+    fn explain(&mut self, part: Part, lines: &[String]) -> Option<Explanation> {
+        let mut explanation = Explanation::new();
+        for (i, line) in lines.iter().enumerate() {
+            let markers: Vec<_> = markers(line, marker_size(part)).collect();
+            explanation.add(
+                format!("line {} markers", i + 1),
+                format!("{} ending at {}", markers.len(), markers.iter().join(", ")),
+            );
+        }
+        Some(explanation)
     }
 }
@@ -1,14 +1,16 @@
-use std::{cell::RefCell, rc::Rc};
-
-use crate::Problem;
+use crate::{
+    arena::{NodeId, Tree},
+    Explanation, Metadata, Part, Problem, Status, Tag,
+};
 
 trait Visitor {
-    fn visit_file_tree(&mut self, node: &FileTree);
+    fn visit_entry(&mut self, id: NodeId<Entry>, entry: &Entry, size_on_disk: usize);
 }
 
 struct FindDirectoriesOfSize {
     size: usize,
     total_size: usize,
+    directories: Vec<(NodeId<Entry>, usize)>,
 }
 
 impl FindDirectoriesOfSize {
@@ -16,23 +18,22 @@ impl FindDirectoriesOfSize {
         FindDirectoriesOfSize {
             size,
             total_size: 0,
+            directories: vec![],
         }
     }
 }
 impl Visitor for FindDirectoriesOfSize {
-    fn visit_file_tree(&mut self, node: &FileTree) {
-        if matches!(node, FileTree::Directory(_)) {
-            let s = node.size_on_disk();
-            if s <= self.size {
-                self.total_size += s;
-            }
+    fn visit_entry(&mut self, id: NodeId<Entry>, entry: &Entry, s: usize) {
+        if matches!(entry, Entry::Directory(_)) && s <= self.size {
+            self.total_size += s;
+            self.directories.push((id, s));
         }
     }
 }
 
 struct FindDirectoriesLargerThan {
     size: usize,
-    smallest_dir: Option<usize>,
+    smallest_dir: Option<(NodeId<Entry>, usize)>,
 }
 
 impl FindDirectoriesLargerThan {
@@ -44,159 +45,133 @@ impl FindDirectoriesLargerThan {
     }
 }
 impl Visitor for FindDirectoriesLargerThan {
-    fn visit_file_tree(&mut self, node: &FileTree) {
-        if matches!(node, FileTree::Directory(_)) {
-            let s = node.size_on_disk();
-            if s >= self.size && self.smallest_dir.map_or(true, |d| s < d) {
-                self.smallest_dir = Some(s);
-            }
+    fn visit_entry(&mut self, id: NodeId<Entry>, entry: &Entry, s: usize) {
+        if matches!(entry, Entry::Directory(_))
+            && s >= self.size
+            && self.smallest_dir.as_ref().is_none_or(|(_, d)| s < *d)
+        {
+            self.smallest_dir = Some((id, s));
         }
     }
 }
 
 #[derive(Debug)]
-struct Directory {
-    name: String,
-    children: RefCell<Vec<Rc<FileTree>>>,
-    size_on_disk: RefCell<Option<usize>>,
+enum Entry {
+    Directory(String),
+    File(String, usize),
 }
 
-impl Directory {
-    fn new<S: Into<String>>(name: S) -> Self {
-        Self {
-            name: name.into(),
-            children: RefCell::new(vec![]),
-            size_on_disk: RefCell::new(None),
-        }
-    }
-
+impl Entry {
     fn name(&self) -> &str {
-        &self.name
-    }
-
-    fn size_on_disk(&self) -> usize {
-        let mut size_on_disk = self.size_on_disk.borrow_mut();
-        match *size_on_disk {
-            Some(s) => s,
-
-            None => {
-                let s = self
-                    .children
-                    .borrow()
-                    .iter()
-                    .map(|n| n.size_on_disk())
-                    .sum();
-                *size_on_disk = Some(s);
-                s
-            }
+        match self {
+            Entry::Directory(n) => n,
+            Entry::File(n, _) => n,
         }
     }
 }
 
-#[derive(Debug)]
-enum FileTree {
-    Directory(Directory),
-    File(String, usize),
+struct FileTree {
+    tree: Tree<Entry>,
+    /// The size on disk of every entry, by id
+    sizes: Vec<usize>,
 }
 
 impl FileTree {
-    fn new_directory<S: Into<String>>(name: S) -> FileTree {
-        FileTree::Directory(Directory::new(name))
-    }
-
-    fn name(&self) -> &str {
-        match self {
-            FileTree::Directory(d) => d.name(),
-            FileTree::File(n, _) => n,
-        }
-    }
-
-    fn add_node(&self, node: FileTree) {
-        match self {
-            FileTree::Directory(d) => d.children.borrow_mut().push(Rc::new(node)),
-            FileTree::File(_, _) => panic!("files can't contain nodes"),
-        }
-    }
-
-    fn directory(&self, name: &str) -> Option<Rc<FileTree>> {
-        match self {
-            FileTree::Directory(d) => d
-                .children
-                .borrow()
-                .iter()
-                .find(|n| n.name() == name)
-                .map(Rc::clone),
-            FileTree::File(_, _) => panic!("files can't contain nodes"),
+    fn new(tree: Tree<Entry>) -> Self {
+        let mut sizes: Vec<_> = tree
+            .ids()
+            .map(|id| match tree[id] {
+                Entry::Directory(_) => 0,
+                Entry::File(_, s) => s,
+            })
+            .collect();
+        // children always come after their parents, so going backwards every entry is complete
+        // before it's added to its parent
+        for id in tree.ids().rev() {
+            if let Some(parent) = tree.parent(id) {
+                sizes[parent.index()] += sizes[id.index()];
+            }
         }
+        Self { tree, sizes }
     }
 
     fn size_on_disk(&self) -> usize {
-        match self {
-            FileTree::Directory(d) => d.size_on_disk(),
-            FileTree::File(_, s) => *s,
-        }
+        self.sizes[self.tree.root().index()]
     }
 
     fn visit<V: Visitor>(&self, visitor: &mut V) {
-        visitor.visit_file_tree(self);
+        for id in self.tree.preorder(self.tree.root()) {
+            visitor.visit_entry(id, &self.tree[id], self.sizes[id.index()]);
+        }
+    }
 
-        if let FileTree::Directory(d) = self {
-            for n in d.children.borrow().iter() {
-                n.visit(visitor);
-            }
+    /// The entry's full path from the root, e.g. `/a/e`, since names repeat across directories
+    fn path(&self, mut id: NodeId<Entry>) -> String {
+        let mut names = vec![];
+        while let Some(parent) = self.tree.parent(id) {
+            names.push(self.tree[id].name());
+            id = parent;
         }
+        names.reverse();
+        format!("/{}", names.join("/"))
     }
 }
 
-fn read_file_tree(lines: &[String]) -> Rc<FileTree> {
-    let root = FileTree::Directory(Directory::new("/"));
-    let mut stack = RefCell::new(vec![Rc::new(root)]);
+fn read_file_tree(lines: &[String]) -> FileTree {
+    let mut tree = Tree::new(Entry::Directory("/".to_owned()));
+    let mut cwd: NodeId<Entry> = tree.root();
 
-    // skip the first one, starting in root directory
-    for l in lines.iter().skip(1) {
+    for l in lines {
         let mut parts = l.split(' ');
         match parts.next().unwrap() {
             "$" => {
                 let command = parts.next().unwrap();
                 match command {
                     "cd" => {
-                        let dirname = parts.next().unwrap();
-                        match dirname {
-                            ".." => {
-                                stack.borrow_mut().pop();
-                            }
-                            dirname => {
-                                let mut stack = stack.borrow_mut();
-                                let node = stack.last().unwrap().directory(dirname).unwrap();
-                                stack.push(node);
-                            }
+                        cwd = match parts.next().unwrap() {
+                            "/" => tree.root(),
+                            ".." => tree.parent(cwd).expect("the root has no parent"),
+                            dirname => tree
+                                .find_child(
+                                    cwd,
+                                    |e| matches!(e, Entry::Directory(n) if n == dirname),
+                                )
+                                .unwrap(),
                         };
                     }
                     "ls" => { /* we don't have to do anything */ }
-                    _ => todo!("not implemented yet"),
+                    _ => panic!("unknown command in {:?}", l),
                 }
             }
-            "dir" => stack
-                .borrow_mut()
-                .last_mut()
-                .unwrap()
-                .add_node(FileTree::new_directory(parts.next().unwrap().to_owned())),
-            v => stack
-                .borrow_mut()
-                .last_mut()
-                .unwrap()
-                .add_node(FileTree::File(
-                    parts.next().unwrap().to_owned(),
-                    v.parse().unwrap(),
-                )),
+            "dir" => {
+                tree.add_child(cwd, Entry::Directory(parts.next().unwrap().to_owned()));
+            }
+            v => {
+                tree.add_child(
+                    cwd,
+                    Entry::File(parts.next().unwrap().to_owned(), v.parse().unwrap()),
+                );
+            }
         }
     }
 
-    stack.get_mut().remove(0)
+    FileTree::new(tree)
 }
 
+const DISK_SIZE: usize = 70_000_000;
+const REQUIRED_SPACE: usize = 30_000_000;
+
 pub struct Problem7;
 impl Problem for Problem7 {
+    fn metadata(&self) -> Metadata {
+        Metadata {
+            title: "No Space Left On Device",
+            part1: Status::Solved,
+            part2: Status::Solved,
+            tags: &[Tag::Parsing],
+        }
+    }
+
     fn solve_part1(&mut self, lines: &[String]) -> String {
         let tree = read_file_tree(lines);
         let mut v = FindDirectoriesOfSize::new(100000);
@@ -205,13 +180,41 @@ impl Problem for Problem7 {
     }
 
     fn solve_part2(&mut self, lines: &[String]) -> String {
-        const DISK_SIZE: usize = 70_000_000;
-        const REQUIRED_SPACE: usize = 30_000_000;
         let tree = read_file_tree(lines);
 
         let free_space = DISK_SIZE - tree.size_on_disk();
         let mut v = FindDirectoriesLargerThan::new(REQUIRED_SPACE - free_space);
         tree.visit(&mut v);
-        v.smallest_dir.unwrap().to_string()
+        v.smallest_dir.unwrap().1.to_string()
+    }
+
+    fn explain(&mut self, part: Part, lines: &[String]) -> Option<Explanation> {
+        let tree = read_file_tree(lines);
+        match part {
+            Part::One => {
+                let mut v = FindDirectoriesOfSize::new(100000);
+                tree.visit(&mut v);
+                Some(
+                    v.directories
+                        .into_iter()
+                        .fold(Explanation::new(), |e, (id, size)| {
+                            e.with(tree.path(id), size)
+                        }),
+                )
+            }
+            Part::Two => {
+                let free_space = DISK_SIZE - tree.size_on_disk();
+                let mut v = FindDirectoriesLargerThan::new(REQUIRED_SPACE - free_space);
+                tree.visit(&mut v);
+                let (id, size) = v.smallest_dir.unwrap();
+                Some(
+                    Explanation::new()
+                        .with("free space", free_space)
+                        .with("space to free", REQUIRED_SPACE - free_space)
+                        .with("deleted directory", tree.path(id))
+                        .with("deleted size", size),
+                )
+            }
+        }
     }
 }
@@ -1,12 +1,14 @@
 use take_until::TakeUntilExt;
 
-use crate::Problem;
+use crate::{
+    grid::Grid,
+    image::{Image, Rgb},
+    terminal::{Canvas, Cell, Color},
+    Metadata, Part, Problem, Status, Tag,
+};
 
-fn read_grid(lines: &[String]) -> Vec<Vec<u32>> {
-    lines
-        .iter()
-        .map(|l| l.chars().map(|c| c.to_digit(10).unwrap()).collect())
-        .collect()
+fn read_grid(lines: &[String]) -> Grid<u32> {
+    Grid::parse(lines, |c| c.to_digit(10).unwrap())
 }
 
 fn is_decreasing<T: PartialOrd + Clone + std::fmt::Debug, I: Iterator<Item = T>>(
@@ -16,16 +18,16 @@ fn is_decreasing<T: PartialOrd + Clone + std::fmt::Debug, I: Iterator<Item = T>>
     iter.all(|t| tree > t)
 }
 
-fn check_tree(grid: &[Vec<u32>], x: usize, y: usize) -> bool {
+fn check_tree(grid: &Grid<u32>, x: usize, y: usize) -> bool {
     [
         // up
-        is_decreasing(grid.iter().take(y + 1).map(|row| row[x]).rev()),
+        is_decreasing(grid.column(x).take(y + 1).rev()),
         // down
-        is_decreasing(grid.iter().skip(y).map(|row| row[x])),
+        is_decreasing(grid.column(x).skip(y)),
         // left
-        is_decreasing(grid[y].iter().take(x + 1).rev()),
+        is_decreasing(grid.row(y).iter().take(x + 1).rev()),
         // right
-        is_decreasing(grid[y].iter().skip(x)),
+        is_decreasing(grid.row(y).iter().skip(x)),
     ]
     .into_iter()
     .any(|p| p)
@@ -38,16 +40,16 @@ fn view_distance<T: PartialOrd + Clone + std::fmt::Debug, I: Iterator<Item = T>>
     iter.take_until(|t| *t >= tree).count()
 }
 
-fn viewing_distance(grid: &[Vec<u32>], x: usize, y: usize) -> u32 {
+fn viewing_distance(grid: &Grid<u32>, x: usize, y: usize) -> u32 {
     [
         // up
-        view_distance(grid.iter().take(y + 1).map(|row| row[x]).rev()),
+        view_distance(grid.column(x).take(y + 1).rev()),
         // down
-        view_distance(grid.iter().skip(y).map(|row| row[x])),
+        view_distance(grid.column(x).skip(y)),
         // left
-        view_distance(grid[y].iter().take(x + 1).rev()),
+        view_distance(grid.row(y).iter().take(x + 1).rev()),
         // right
-        view_distance(grid[y].iter().skip(x)),
+        view_distance(grid.row(y).iter().skip(x)),
     ]
     .into_iter()
     .product::<usize>() as u32
@@ -55,13 +57,22 @@ fn viewing_distance(grid: &[Vec<u32>], x: usize, y: usize) -> u32 {
 
 pub struct Problem8;
 impl Problem for Problem8 {
+    fn metadata(&self) -> Metadata {
+        Metadata {
+            title: "Treetop Tree House",
+            part1: Status::Solved,
+            part2: Status::Solved,
+            tags: &[Tag::Grid],
+        }
+    }
+
     fn solve_part1(&mut self, lines: &[String]) -> String {
         let grid = read_grid(lines);
 
         // add the permiter, subtract 4 because each corner gets double-counted
-        let mut count = grid.len() * 2 + grid[0].len() * 2 - 4;
-        for y in 1..(grid.len() - 1) {
-            for x in 1..(grid[0].len() - 1) {
+        let mut count = grid.height() * 2 + grid.width() * 2 - 4;
+        for y in 1..(grid.height() - 1) {
+            for x in 1..(grid.width() - 1) {
                 if check_tree(&grid, x, y) {
                     count += 1;
                 }
@@ -75,11 +86,64 @@ impl Problem for Problem8 {
 
         // add the permiter, subtract 4 because each corner gets double-counted
         let mut distance = 0;
-        for y in 1..(grid.len() - 1) {
-            for x in 1..(grid[0].len() - 1) {
+        for y in 1..(grid.height() - 1) {
+            for x in 1..(grid.width() - 1) {
                 distance = distance.max(viewing_distance(&grid, x, y));
             }
         }
         distance.to_string()
     }
+
+    /// Trees as bright as they're tall, with the visible ones in green for part 1 and the one
+    /// with the best view in red for part 2
+    fn render(&mut self, part: Part, lines: &[String]) -> Option<Image> {
+        let grid = read_grid(lines);
+        let shade = |height: u32| Rgb::shade(height as i64, 0, 9);
+        let mut image = Image::from_grid(&grid, |&height| shade(height));
+        match part {
+            Part::One => {
+                for (p, &height) in grid.iter() {
+                    if check_tree(&grid, p.x() as usize, p.y() as usize) {
+                        image[p] = Rgb(0, shade(height).1, 0);
+                    }
+                }
+            }
+            Part::Two => {
+                let best = grid
+                    .points()
+                    .max_by_key(|p| viewing_distance(&grid, p.x() as usize, p.y() as usize))?;
+                image[best] = Rgb::RED;
+            }
+        }
+        Some(image)
+    }
+
+    /// The tree heights, with the visible trees in green for part 1 and the one with the best
+    /// view in red for part 2
+    fn show(&mut self, part: Part, lines: &[String]) -> Option<Canvas> {
+        let grid = read_grid(lines);
+        let mut canvas = Canvas::from_grid(&grid, |&height| char::from_digit(height, 10).unwrap());
+        match part {
+            Part::One => {
+                for p in grid.points() {
+                    if check_tree(&grid, p.x() as usize, p.y() as usize) {
+                        canvas.highlight(p, Color::Green, '*');
+                    }
+                }
+                canvas.add_legend(
+                    Cell::colored('9', Color::Green).with_marker('*'),
+                    "visible from outside",
+                );
+            }
+            Part::Two => {
+                let best = grid
+                    .points()
+                    .max_by_key(|p| viewing_distance(&grid, p.x() as usize, p.y() as usize))?;
+                canvas.highlight(best, Color::Red, '*');
+                canvas.set_focus(best);
+                canvas.add_legend(Cell::colored('9', Color::Red).with_marker('*'), "best view");
+            }
+        }
+        Some(canvas)
+    }
 }
@@ -1,7 +1,11 @@
 use std::collections::HashSet;
 
-use crate::point::Point;
-use crate::Problem;
+use crate::point::{Direction, Point};
+use crate::{recording, Metadata, Problem, Status, Tag};
+
+/// How far from the head a frame shows, across and up and down
+const VIEW_WIDTH: i32 = 20;
+const VIEW_HEIGHT: i32 = 10;
 
 #[derive(Debug, Clone)]
 struct Snake {
@@ -11,71 +15,92 @@ struct Snake {
 impl Snake {
     fn new(length: usize) -> Self {
         Self {
-            body: std::iter::repeat(Point::new(0, 0)).take(length).collect(),
+            body: std::iter::repeat_n(Point::new(0, 0), length).collect(),
         }
     }
 
-    fn shift(&mut self, x: i32, y: i32) {
-        self.body[0].shift(x, y);
+    fn shift(&mut self, direction: Direction) {
+        self.body[0] += direction.unit();
         let mut head = self.body[0];
         for tail in self.body.iter_mut().skip(1) {
-            let (xdist, ydist) = (head.x() - tail.x(), head.y() - tail.y());
-            if xdist.abs() == 2 || ydist.abs() == 2 {
-                tail.shift(xdist.signum(), ydist.signum());
+            if head.chebyshev_distance(tail) == 2 {
+                let d = head - *tail;
+                tail.shift(d.x().signum(), d.y().signum());
             }
             head = *tail;
         }
     }
 }
 
+/// The rope around its head, with the squares the tail has visited
+fn draw(snake: &Snake, visited: &HashSet<Point>) -> String {
+    let head = snake.body[0];
+    let mut frame = String::new();
+    // up is +y, so the top row comes first
+    for y in (head.y() - VIEW_HEIGHT..=head.y() + VIEW_HEIGHT).rev() {
+        for x in head.x() - VIEW_WIDTH..=head.x() + VIEW_WIDTH {
+            let p = Point::new(x, y);
+            let c = match snake.body.iter().position(|&k| k == p) {
+                Some(0) => 'H',
+                Some(_) if snake.body.len() == 2 => 'T',
+                Some(i) => char::from_digit(i as u32, 10).unwrap_or('#'),
+                None if p == Point::origin() => 's',
+                None if visited.contains(&p) => '#',
+                None => '.',
+            };
+            frame.push(c);
+        }
+        frame.push('\n');
+    }
+    frame
+}
+
+/// One direction per step the head takes
+fn read_moves(lines: &[String]) -> impl Iterator<Item = Direction> + '_ {
+    lines
+        .iter()
+        .map(|l| l.split_once(' ').unwrap())
+        .flat_map(|(dir, n)| {
+            let direction: Direction = dir.parse().unwrap();
+            std::iter::repeat_n(direction, n.parse().unwrap())
+        })
+}
+
 pub struct Problem9;
 impl Problem for Problem9 {
+    fn metadata(&self) -> Metadata {
+        Metadata {
+            title: "Rope Bridge",
+            part1: Status::Solved,
+            part2: Status::Solved,
+            tags: &[Tag::Simulation, Tag::Grid],
+        }
+    }
+
     fn solve_part1(&mut self, lines: &[String]) -> String {
-        let moves = lines
-            .iter()
-            .map(|l| l.split_once(' ').unwrap())
-            .flat_map(|(dir, n)| {
-                let n: i32 = n.parse().unwrap();
-                match dir {
-                    "R" => std::iter::repeat((1, 0)).take(n as usize),
-                    "L" => std::iter::repeat((-1, 0)).take(n.abs() as usize),
-                    "U" => std::iter::repeat((0, 1)).take(n as usize),
-                    "D" => std::iter::repeat((0, -1)).take(n.abs() as usize),
-                    _ => panic!("invalid direction"),
-                }
-            });
+        let moves = read_moves(lines);
 
         let mut snake = Snake::new(2);
         let mut tails: HashSet<Point, _> = HashSet::new();
         tails.extend(snake.body.iter().skip(1));
-        for (x, y) in moves {
-            snake.shift(x, y);
+        for direction in moves {
+            snake.shift(direction);
             tails.extend(snake.body.iter().skip(1));
+            recording::frame(|| draw(&snake, &tails));
         }
         tails.len().to_string()
     }
 
     fn solve_part2(&mut self, lines: &[String]) -> String {
-        let moves = lines
-            .iter()
-            .map(|l| l.split_once(' ').unwrap())
-            .flat_map(|(dir, n)| {
-                let n: i32 = n.parse().unwrap();
-                match dir {
-                    "R" => std::iter::repeat((1, 0)).take(n as usize),
-                    "L" => std::iter::repeat((-1, 0)).take(n.abs() as usize),
-                    "U" => std::iter::repeat((0, 1)).take(n as usize),
-                    "D" => std::iter::repeat((0, -1)).take(n.abs() as usize),
-                    _ => panic!("invalid direction"),
-                }
-            });
+        let moves = read_moves(lines);
 
         let mut snake = Snake::new(10);
         let mut tails: HashSet<Point, _> = HashSet::new();
         tails.insert(*snake.body.last().unwrap());
-        for (x, y) in moves {
-            snake.shift(x, y);
+        for direction in moves {
+            snake.shift(direction);
             tails.insert(*snake.body.last().unwrap());
+            recording::frame(|| draw(&snake, &tails));
         }
         tails.len().to_string()
     }
@@ -0,0 +1,139 @@
+//! Frame-by-frame recordings of simulations, to be replayed in a terminal player. Solvers hand
+//! over a frame after each step of their simulation, the runner decides whether to record and
+//! saves what was recorded, so solvers cost nothing extra when no one is watching.
+//!
+//! Recordings are saved as asciicast v2, the format `asciinema play` reads: a JSON header line,
+//! then one line per frame with its time in seconds and the text that redraws the screen.
+
+use std::{
+    cell::RefCell,
+    fs::{self, File},
+    io::{self, BufWriter, Write},
+    path::Path,
+};
+
+use crate::json_string;
+
+thread_local! {
+    static RECORDING: RefCell<Option<Recording>> = const { RefCell::new(None) };
+}
+
+/// Clears the screen and moves the cursor to the top left
+const CLEAR: &str = "\x1b[2J\x1b[H";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Recording {
+    fps: u32,
+    max_frames: usize,
+    frames: Vec<String>,
+}
+
+impl Recording {
+    /// A recording played back at `fps` frames a second that keeps the first `max_frames`
+    pub fn new(fps: u32, max_frames: usize) -> Self {
+        Self {
+            fps: fps.max(1),
+            max_frames,
+            frames: vec![],
+        }
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.frames.len() >= self.max_frames
+    }
+
+    /// Adds a frame unless the recording is full, lines are separated by `\n`
+    pub fn push(&mut self, frame: String) {
+        if !self.is_full() {
+            self.frames.push(frame);
+        }
+    }
+
+    pub fn frames(&self) -> &[String] {
+        &self.frames
+    }
+
+    /// The widest line of any frame, in characters
+    pub fn width(&self) -> usize {
+        self.frames
+            .iter()
+            .flat_map(|f| f.lines())
+            .map(|l| l.chars().count())
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// The most lines in any frame
+    pub fn height(&self) -> usize {
+        self.frames
+            .iter()
+            .map(|f| f.lines().count())
+            .max()
+            .unwrap_or(0)
+    }
+
+    pub fn write_asciicast(&self, out: &mut impl Write) -> io::Result<()> {
+        writeln!(
+            out,
+            "{{\"version\": 2, \"width\": {}, \"height\": {}}}",
+            self.width(),
+            self.height()
+        )?;
+        for (i, frame) in self.frames.iter().enumerate() {
+            // terminals need a carriage return to go back to the start of the line
+            let screen = format!("{}{}", CLEAR, frame.replace('\n', "\r\n"));
+            writeln!(
+                out,
+                "[{:.6}, \"o\", {}]",
+                i as f64 / self.fps as f64,
+                json_string(&screen)
+            )?;
+        }
+        Ok(())
+    }
+
+    pub fn save_asciicast(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let mut out = BufWriter::new(File::create(path)?);
+        self.write_asciicast(&mut out)?;
+        out.flush()
+    }
+
+    /// Saves every frame as its own text file in `directory`, numbered from `00001.txt`
+    pub fn save_frames(&self, directory: impl AsRef<Path>) -> io::Result<()> {
+        let directory = directory.as_ref();
+        fs::create_dir_all(directory)?;
+        for (i, frame) in self.frames.iter().enumerate() {
+            fs::write(directory.join(format!("{:05}.txt", i + 1)), frame)?;
+        }
+        Ok(())
+    }
+}
+
+/// Starts recording the frames solvers hand over on this thread, replacing any recording
+/// already going
+pub fn start(recording: Recording) {
+    RECORDING.with(|r| *r.borrow_mut() = Some(recording));
+}
+
+/// Whether a frame handed over now would be kept
+pub fn is_recording() -> bool {
+    RECORDING.with(|r| r.borrow().as_ref().is_some_and(|r| !r.is_full()))
+}
+
+/// Records the frame `draw` outputs. `draw` is only called while recording, so drawing costs
+/// nothing otherwise.
+pub fn frame(draw: impl FnOnce() -> String) {
+    if is_recording() {
+        let frame = draw();
+        RECORDING.with(|r| {
+            if let Some(recording) = r.borrow_mut().as_mut() {
+                recording.push(frame);
+            }
+        });
+    }
+}
+
+/// Stops recording and outputs what was recorded, `None` if nothing was being recorded
+pub fn finish() -> Option<Recording> {
+    RECORDING.with(|r| r.borrow_mut().take())
+}
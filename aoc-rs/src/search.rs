@@ -0,0 +1,275 @@
+//! Graph searches over any node type. The graph is never built up front: each search is given
+//! a function listing a node's neighbors, so grids can use points as nodes and labeled graphs
+//! their labels.
+//!
+//! Every search takes any number of starts and a goal predicate, and stops at the first goal it
+//! reaches. Pass `|_| false` as the goal to explore everything reachable.
+
+use std::{
+    cmp::Ordering,
+    collections::{hash_map::Entry, BinaryHeap, HashMap, VecDeque},
+    hash::Hash,
+    ops::Add,
+};
+
+use crate::counters;
+
+/// Edge weights and path costs. `Default` is taken to be the zero cost.
+pub trait Cost: Copy + Ord + Add<Output = Self> + Default {}
+
+impl<T: Copy + Ord + Add<Output = T> + Default> Cost for T {}
+
+/// What a search reached: the cheapest known cost to each node, the node it was reached from,
+/// and the goal it stopped at, if any
+#[derive(Debug, Clone)]
+pub struct Search<N, C> {
+    costs: HashMap<N, C>,
+    parents: HashMap<N, N>,
+    goal: Option<N>,
+}
+
+impl<N: Clone + Eq + Hash, C: Copy> Search<N, C> {
+    fn new() -> Self {
+        Self {
+            costs: HashMap::new(),
+            parents: HashMap::new(),
+            goal: None,
+        }
+    }
+
+    pub fn cost(&self, node: &N) -> Option<C> {
+        self.costs.get(node).copied()
+    }
+
+    pub fn reached(&self, node: &N) -> bool {
+        self.costs.contains_key(node)
+    }
+
+    /// Every node reached along with its cost, in no particular order
+    pub fn costs(&self) -> impl Iterator<Item = (&N, C)> {
+        self.costs.iter().map(|(n, &c)| (n, c))
+    }
+
+    pub fn goal(&self) -> Option<&N> {
+        self.goal.as_ref()
+    }
+
+    pub fn goal_cost(&self) -> Option<C> {
+        self.goal.as_ref().and_then(|g| self.cost(g))
+    }
+
+    /// The node `node` was first reached from, `None` for the starts and unreached nodes
+    pub fn parent(&self, node: &N) -> Option<&N> {
+        self.parents.get(node)
+    }
+
+    /// The nodes from the start that reached `node` to `node` itself, both included
+    pub fn path_to(&self, node: &N) -> Option<Vec<N>> {
+        if !self.reached(node) {
+            return None;
+        }
+
+        let mut path = vec![node.clone()];
+        while let Some(parent) = self.parents.get(path.last().unwrap()) {
+            path.push(parent.clone());
+        }
+        path.reverse();
+        Some(path)
+    }
+
+    /// The path to the goal the search stopped at
+    pub fn path(&self) -> Option<Vec<N>> {
+        self.goal.as_ref().and_then(|g| self.path_to(g))
+    }
+}
+
+/// Breadth-first search over unweighted edges, the cost of a node is its number of steps from
+/// the closest start
+pub fn bfs<N, I>(
+    starts: impl IntoIterator<Item = N>,
+    mut neighbors: impl FnMut(&N) -> I,
+    mut is_goal: impl FnMut(&N) -> bool,
+) -> Search<N, usize>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = N>,
+{
+    let mut search = Search::new();
+    let mut queue = VecDeque::new();
+    for start in starts {
+        if let Entry::Vacant(e) = search.costs.entry(start.clone()) {
+            e.insert(0);
+            queue.push_back(start);
+        }
+    }
+
+    while let Some(node) = queue.pop_front() {
+        counters::increment("nodes expanded");
+        if is_goal(&node) {
+            search.goal = Some(node);
+            break;
+        }
+
+        let cost = search.costs[&node] + 1;
+        for next in neighbors(&node) {
+            if let Entry::Vacant(e) = search.costs.entry(next.clone()) {
+                e.insert(cost);
+                search.parents.insert(next.clone(), node.clone());
+                queue.push_back(next);
+            }
+        }
+    }
+
+    search
+}
+
+/// Cheapest-first search over weighted edges, `neighbors` outputs each neighbor with the cost
+/// of the edge to it
+pub fn dijkstra<N, C, I>(
+    starts: impl IntoIterator<Item = N>,
+    neighbors: impl FnMut(&N) -> I,
+    is_goal: impl FnMut(&N) -> bool,
+) -> Search<N, C>
+where
+    N: Clone + Eq + Hash,
+    C: Cost,
+    I: IntoIterator<Item = (N, C)>,
+{
+    astar(starts, neighbors, |_| C::default(), is_goal)
+}
+
+/// Like `dijkstra`, but nodes are explored in order of their cost plus `heuristic`'s estimate
+/// of the cost left to a goal. The estimate must never be more than the actual cost, or the path
+/// found might not be the cheapest.
+pub fn astar<N, C, I>(
+    starts: impl IntoIterator<Item = N>,
+    mut neighbors: impl FnMut(&N) -> I,
+    mut heuristic: impl FnMut(&N) -> C,
+    mut is_goal: impl FnMut(&N) -> bool,
+) -> Search<N, C>
+where
+    N: Clone + Eq + Hash,
+    C: Cost,
+    I: IntoIterator<Item = (N, C)>,
+{
+    let mut search = Search::new();
+    let mut queue = BinaryHeap::new();
+    for start in starts {
+        if let Entry::Vacant(e) = search.costs.entry(start.clone()) {
+            e.insert(C::default());
+            queue.push(Queued {
+                priority: heuristic(&start),
+                cost: C::default(),
+                node: start,
+            });
+        }
+    }
+
+    while let Some(Queued { cost, node, .. }) = queue.pop() {
+        // a cheaper way here was found after this one was queued. A node can still be expanded
+        // twice, when the heuristic overestimated the way it was first reached.
+        if cost > search.costs[&node] {
+            continue;
+        }
+
+        counters::increment("nodes expanded");
+        if is_goal(&node) {
+            search.goal = Some(node);
+            break;
+        }
+
+        for (next, edge) in neighbors(&node) {
+            let next_cost = cost + edge;
+            let better = match search.costs.get(&next) {
+                Some(&known) => next_cost < known,
+                None => true,
+            };
+            if better {
+                search.costs.insert(next.clone(), next_cost);
+                search.parents.insert(next.clone(), node.clone());
+                queue.push(Queued {
+                    priority: next_cost + heuristic(&next),
+                    cost: next_cost,
+                    node: next,
+                });
+            }
+        }
+    }
+
+    search
+}
+
+/// A node waiting in the priority queue, ordered so the lowest priority is popped first
+struct Queued<N, C> {
+    priority: C,
+    cost: C,
+    node: N,
+}
+
+impl<N, C: Ord> PartialEq for Queued<N, C> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<N, C: Ord> Eq for Queued<N, C> {}
+
+impl<N, C: Ord> PartialOrd for Queued<N, C> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<N, C: Ord> Ord for Queued<N, C> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // reversed for BinaryHeap, ties go to the most expensive i.e. closest to a goal
+        other
+            .priority
+            .cmp(&self.priority)
+            .then_with(|| self.cost.cmp(&other.cost))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// S→A 1, S→B 4, A→B 1, B→G 5: the cheapest way to G goes through A, for 7
+    fn edges(node: &char) -> Vec<(char, u32)> {
+        match node {
+            'S' => vec![('A', 1), ('B', 4)],
+            'A' => vec![('B', 1)],
+            'B' => vec![('G', 5)],
+            _ => vec![],
+        }
+    }
+
+    #[test]
+    fn dijkstra_finds_the_cheapest_path() {
+        let search = dijkstra(['S'], edges, |&n| n == 'G');
+        assert_eq!(search.goal_cost(), Some(7));
+        assert_eq!(search.path(), Some(vec!['S', 'A', 'B', 'G']));
+    }
+
+    #[test]
+    fn astar_reexpands_nodes_reached_more_cheaply() {
+        // admissible, but not consistent: it makes B look closer from S than through A
+        let heuristic = |n: &char| match n {
+            'A' => 5,
+            _ => 0,
+        };
+        let search = astar(['S'], edges, heuristic, |&n| n == 'G');
+        assert_eq!(search.goal_cost(), Some(7));
+        assert_eq!(search.cost(&'B'), Some(2));
+        assert_eq!(search.path(), Some(vec!['S', 'A', 'B', 'G']));
+    }
+
+    #[test]
+    fn bfs_counts_steps_from_the_closest_start() {
+        let line = |&n: &i32| [n - 1, n + 1].into_iter().filter(|n| (0..10).contains(n));
+        let search = bfs([0, 9], line, |_| false);
+        assert_eq!(search.cost(&3), Some(3));
+        assert_eq!(search.cost(&7), Some(2));
+        assert_eq!(search.path_to(&6), Some(vec![9, 8, 7, 6]));
+    }
+}
@@ -0,0 +1,220 @@
+//! Text pictures of grids for the terminal, with cells highlighted in color. Colors are ANSI
+//! escape codes, only used when stdout is a terminal, so output piped to a file or another
+//! program stays plain text; highlighted cells can give a marker to be drawn instead there.
+
+use std::{
+    env,
+    io::{self, IsTerminal},
+};
+
+use crate::{grid::Grid, point::Point};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Color {
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    Gray,
+}
+
+impl Color {
+    /// The SGR code setting this as the foreground color
+    fn code(self) -> u8 {
+        match self {
+            Color::Red => 31,
+            Color::Green => 32,
+            Color::Yellow => 33,
+            Color::Blue => 34,
+            Color::Magenta => 35,
+            Color::Cyan => 36,
+            Color::Gray => 90,
+        }
+    }
+}
+
+/// A character, in the terminal's default color unless `color` says otherwise. Without colors
+/// the cell shows `marker` instead, when it has one.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Cell {
+    pub symbol: char,
+    pub color: Option<Color>,
+    pub marker: Option<char>,
+}
+
+impl Cell {
+    pub fn plain(symbol: char) -> Self {
+        Self {
+            symbol,
+            color: None,
+            marker: None,
+        }
+    }
+
+    pub fn colored(symbol: char, color: Color) -> Self {
+        Self {
+            symbol,
+            color: Some(color),
+            marker: None,
+        }
+    }
+
+    /// Shows `marker` instead of the symbol when colors are off, for cells only their color
+    /// would set apart
+    pub fn with_marker(self, marker: char) -> Self {
+        Self {
+            marker: Some(marker),
+            ..self
+        }
+    }
+}
+
+/// Whether stdout can show colors: it's a terminal, and `NO_COLOR` isn't set
+pub fn colors_enabled() -> bool {
+    io::stdout().is_terminal() && env::var_os("NO_COLOR").is_none()
+}
+
+/// A grid of cells with a legend explaining them. (0, 0) is the top left cell, and drawing
+/// outside the canvas does nothing, so callers don't need to clip.
+#[derive(Debug, Clone)]
+pub struct Canvas {
+    cells: Grid<Cell>,
+    legend: Vec<(Cell, String)>,
+    focus: Option<Point>,
+}
+
+impl Canvas {
+    pub fn new(width: usize, height: usize, background: char) -> Self {
+        Self {
+            cells: Grid::new(width, height, Cell::plain(background)),
+            legend: vec![],
+            focus: None,
+        }
+    }
+
+    /// One plain cell per grid cell, with the character `symbol` outputs for it
+    pub fn from_grid<T>(grid: &Grid<T>, mut symbol: impl FnMut(&T) -> char) -> Self {
+        Self {
+            cells: grid.map(|t| Cell::plain(symbol(t))),
+            legend: vec![],
+            focus: None,
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.cells.width()
+    }
+
+    pub fn height(&self) -> usize {
+        self.cells.height()
+    }
+
+    pub fn get(&self, p: Point) -> Option<Cell> {
+        self.cells.get(p).copied()
+    }
+
+    pub fn put(&mut self, p: Point, cell: Cell) {
+        if let Some(c) = self.cells.get_mut(p) {
+            *c = cell;
+        }
+    }
+
+    /// Colors the character already at `p`, which becomes `marker` when colors are off
+    pub fn highlight(&mut self, p: Point, color: Color, marker: char) {
+        if let Some(c) = self.cells.get_mut(p) {
+            *c = Cell::colored(c.symbol, color).with_marker(marker);
+        }
+    }
+
+    /// Explains what `cell` stands for, below the picture
+    pub fn add_legend(&mut self, cell: Cell, meaning: impl Into<String>) {
+        self.legend.push((cell, meaning.into()));
+    }
+
+    pub fn with_legend(mut self, cell: Cell, meaning: impl Into<String>) -> Self {
+        self.add_legend(cell, meaning);
+        self
+    }
+
+    /// Marks the most interesting cell, which viewports are centered on
+    pub fn set_focus(&mut self, p: Point) {
+        self.focus = Some(p);
+    }
+
+    /// At most `width` by `height` cells around the focus, or the middle when there is none,
+    /// moved as needed to stay inside the canvas. The legend is kept.
+    pub fn viewport(&self, width: usize, height: usize) -> Self {
+        let width = width.min(self.width());
+        let height = height.min(self.height());
+        let focus = self.focus.unwrap_or(Point::new(
+            self.width() as i32 / 2,
+            self.height() as i32 / 2,
+        ));
+        let start = |center: i32, size: usize, total: usize| {
+            (center - size as i32 / 2).clamp(0, (total - size) as i32)
+        };
+        let left = start(focus.x(), width, self.width());
+        let top = start(focus.y(), height, self.height());
+
+        let rows = (0..height)
+            .map(|y| {
+                let row = self.cells.row(top as usize + y);
+                row[left as usize..left as usize + width].to_vec()
+            })
+            .collect();
+        Self {
+            cells: Grid::from_rows(rows),
+            legend: self.legend.clone(),
+            focus: self.focus.map(|f| f - Point::new(left, top)),
+        }
+    }
+
+    /// The picture and its legend as lines of text, with escape codes for the colors when
+    /// `colors` is set
+    pub fn render(&self, colors: bool) -> String {
+        let mut out = String::new();
+        for row in self.cells.rows() {
+            render_cells(&mut out, row, colors);
+            out.push('\n');
+        }
+        for (cell, meaning) in &self.legend {
+            out.push_str("  ");
+            render_cells(&mut out, &[*cell], colors);
+            out.push(' ');
+            out.push_str(meaning);
+            out.push('\n');
+        }
+        out
+    }
+
+    /// Prints the picture, in color if stdout can show it
+    pub fn print(&self) {
+        print!("{}", self.render(colors_enabled()));
+    }
+}
+
+/// Appends the cells, switching colors only where they change and resetting at the end, or
+/// with their markers when there are no colors
+fn render_cells(out: &mut String, cells: &[Cell], colors: bool) {
+    if !colors {
+        out.extend(cells.iter().map(|c| c.marker.unwrap_or(c.symbol)));
+        return;
+    }
+
+    let mut current = None;
+    for cell in cells {
+        if cell.color != current {
+            match cell.color {
+                Some(color) => out.push_str(&format!("\x1b[{}m", color.code())),
+                None => out.push_str("\x1b[0m"),
+            }
+            current = cell.color;
+        }
+        out.push(cell.symbol);
+    }
+    if current.is_some() {
+        out.push_str("\x1b[0m");
+    }
+}
@@ -0,0 +1,82 @@
+//! Picking the few largest or smallest items of a stream without sorting or even keeping the
+//! rest. A heap of at most `k` candidates holds the best so far with the worst on top, so each
+//! new item is compared against it and either dropped or swapped in.
+
+use std::{cmp::Ordering, collections::BinaryHeap};
+
+/// An item with where it was in the stream, ordered so the better of two compares greater.
+/// Between equal items, the one seen first is better.
+struct Candidate<T> {
+    item: T,
+    index: usize,
+    largest: bool,
+}
+
+impl<T: Ord> Ord for Candidate<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let by_item = match self.largest {
+            true => self.item.cmp(&other.item),
+            false => other.item.cmp(&self.item),
+        };
+        by_item.then(other.index.cmp(&self.index))
+    }
+}
+
+impl<T: Ord> PartialOrd for Candidate<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T: Ord> PartialEq for Candidate<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<T: Ord> Eq for Candidate<T> {}
+
+fn select<T: Ord>(items: impl Iterator<Item = T>, k: usize, largest: bool) -> Vec<(usize, T)> {
+    if k == 0 {
+        return vec![];
+    }
+
+    // reversed, so the worst candidate is on top
+    let mut heap = BinaryHeap::with_capacity(k + 1);
+    for (index, item) in items.enumerate() {
+        let candidate = std::cmp::Reverse(Candidate {
+            item,
+            index,
+            largest,
+        });
+        if heap.len() < k {
+            heap.push(candidate);
+        } else if heap.peek().is_some_and(|worst| candidate < *worst) {
+            heap.pop();
+            heap.push(candidate);
+        }
+    }
+
+    heap.into_sorted_vec()
+        .into_iter()
+        .map(|std::cmp::Reverse(c)| (c.index, c.item))
+        .collect()
+}
+
+pub trait TopK: Iterator + Sized
+where
+    Self::Item: Ord,
+{
+    /// The `k` largest items with their index in the iterator, largest first. Equal items are
+    /// kept in the order they came.
+    fn top_k(self, k: usize) -> Vec<(usize, Self::Item)> {
+        select(self, k, true)
+    }
+
+    /// The `k` smallest items with their index in the iterator, smallest first
+    fn bottom_k(self, k: usize) -> Vec<(usize, Self::Item)> {
+        select(self, k, false)
+    }
+}
+
+impl<I: Iterator> TopK for I where I::Item: Ord {}
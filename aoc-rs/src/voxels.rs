@@ -0,0 +1,108 @@
+use std::collections::HashSet;
+
+use crate::{
+    point::{Coordinate, Point3D},
+    search,
+};
+
+/// A sparse set of unit cubes on an integer grid. Two voxels share a face when they're one step
+/// apart along a single axis.
+#[derive(Debug, Clone, Default)]
+pub struct VoxelSet<T = i32> {
+    voxels: HashSet<Point3D<T>>,
+}
+
+impl<T: Coordinate> VoxelSet<T> {
+    pub fn new() -> Self {
+        Self {
+            voxels: HashSet::new(),
+        }
+    }
+
+    /// Outputs whether the voxel was newly added
+    pub fn insert(&mut self, voxel: Point3D<T>) -> bool {
+        self.voxels.insert(voxel)
+    }
+
+    pub fn contains(&self, voxel: &Point3D<T>) -> bool {
+        self.voxels.contains(voxel)
+    }
+
+    pub fn len(&self) -> usize {
+        self.voxels.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.voxels.is_empty()
+    }
+
+    /// The voxels in no particular order
+    pub fn iter(&self) -> impl Iterator<Item = &Point3D<T>> {
+        self.voxels.iter()
+    }
+
+    /// The lowest and highest corners of the smallest box holding every voxel, `None` when empty
+    pub fn bounds(&self) -> Option<(Point3D<T>, Point3D<T>)> {
+        let mut voxels = self.voxels.iter();
+        let first = *voxels.next()?;
+        Some(voxels.fold((first, first), |(min, max), v| {
+            (min.component_min(v), max.component_max(v))
+        }))
+    }
+
+    /// The six voxels sharing a face with `voxel`, whether they're in the set or not
+    pub fn neighbors(voxel: &Point3D<T>) -> impl Iterator<Item = Point3D<T>> {
+        voxel.neighbors6()
+    }
+
+    /// How many faces of the set's voxels touch a voxel in `other`
+    pub fn shared_faces(&self, other: &Self) -> usize {
+        self.voxels
+            .iter()
+            .flat_map(Self::neighbors)
+            .filter(|n| other.contains(n))
+            .count()
+    }
+
+    /// How many faces don't touch another voxel of the set, air pockets inside included
+    pub fn surface_area(&self) -> usize {
+        self.voxels.len() * 6 - self.shared_faces(self)
+    }
+
+    /// Every empty voxel that can be reached from outside the bounding box without passing
+    /// through the set. The fill is kept to the bounding box grown by one voxel each way, which is
+    /// enough to wrap all the way around the set.
+    pub fn exterior(&self) -> Self {
+        let Some((min, max)) = self.bounds() else {
+            return Self::new();
+        };
+        let one = Point3D::new(T::ONE, T::ONE, T::ONE);
+        let (min, max) = (min - one, max + one);
+        let inside =
+            |v: &Point3D<T>| (0..3).all(|axis| min[axis] <= v[axis] && v[axis] <= max[axis]);
+
+        let fill = search::bfs(
+            [min],
+            |v| {
+                Self::neighbors(v)
+                    .filter(|n| inside(n) && !self.contains(n))
+                    .collect::<Vec<_>>()
+            },
+            |_| false,
+        );
+        fill.costs().map(|(&v, _)| v).collect()
+    }
+
+    /// How many faces touch the exterior, leaving out the insides of air pockets
+    pub fn exterior_surface_area(&self) -> usize {
+        self.shared_faces(&self.exterior())
+    }
+}
+
+impl<T: Coordinate> FromIterator<Point3D<T>> for VoxelSet<T> {
+    fn from_iter<I: IntoIterator<Item = Point3D<T>>>(iter: I) -> Self {
+        Self {
+            voxels: iter.into_iter().collect(),
+        }
+    }
+}
@@ -0,0 +1,152 @@
+//! Sliding windows that know how many distinct items they hold. Pushing and popping an item
+//! updates a tally of each item's count in the window, so checking whether every item differs
+//! costs the same however wide the window is.
+
+use std::{
+    collections::{HashMap, VecDeque},
+    hash::Hash,
+};
+
+/// How many of each item a window holds
+pub trait Tally<T> {
+    /// Counts one more `item`, outputs how many there are now
+    fn add(&mut self, item: &T) -> usize;
+
+    /// Counts one less `item`, outputs how many are left
+    fn remove(&mut self, item: &T) -> usize;
+}
+
+impl<T: Hash + Eq + Clone> Tally<T> for HashMap<T, usize> {
+    fn add(&mut self, item: &T) -> usize {
+        let count = self.entry(item.clone()).or_insert(0);
+        *count += 1;
+        *count
+    }
+
+    fn remove(&mut self, item: &T) -> usize {
+        let count = self
+            .get_mut(item)
+            .expect("removed an item that wasn't counted");
+        *count -= 1;
+        let left = *count;
+        if left == 0 {
+            HashMap::remove(self, item);
+        }
+        left
+    }
+}
+
+/// Bytes are counted in a table instead, with no hashing at all
+impl Tally<u8> for [usize; 256] {
+    fn add(&mut self, item: &u8) -> usize {
+        self[*item as usize] += 1;
+        self[*item as usize]
+    }
+
+    fn remove(&mut self, item: &u8) -> usize {
+        self[*item as usize] -= 1;
+        self[*item as usize]
+    }
+}
+
+/// The last `size` items pushed, with how many of them are distinct
+#[derive(Debug, Clone)]
+pub struct DistinctWindow<T, C = HashMap<T, usize>> {
+    size: usize,
+    items: VecDeque<T>,
+    tally: C,
+    distinct: usize,
+}
+
+impl<T: Hash + Eq + Clone> DistinctWindow<T> {
+    pub fn new(size: usize) -> Self {
+        Self::with_tally(size, HashMap::new())
+    }
+}
+
+impl DistinctWindow<u8, [usize; 256]> {
+    pub fn bytes(size: usize) -> Self {
+        Self::with_tally(size, [0; 256])
+    }
+}
+
+impl<T, C: Tally<T>> DistinctWindow<T, C> {
+    /// A window counting its items in `tally`, which must start out empty
+    pub fn with_tally(size: usize, tally: C) -> Self {
+        Self {
+            size,
+            items: VecDeque::with_capacity(size),
+            tally,
+            distinct: 0,
+        }
+    }
+
+    /// Adds `item` as the newest, outputs the oldest item when it had to make room for it
+    pub fn push(&mut self, item: T) -> Option<T> {
+        let evicted = match self.items.len() >= self.size {
+            true => self.pop(),
+            false => None,
+        };
+        if self.size == 0 {
+            return Some(item);
+        }
+
+        if self.tally.add(&item) == 1 {
+            self.distinct += 1;
+        }
+        self.items.push_back(item);
+        evicted
+    }
+
+    /// Takes out the oldest item
+    pub fn pop(&mut self) -> Option<T> {
+        let item = self.items.pop_front()?;
+        if self.tally.remove(&item) == 0 {
+            self.distinct -= 1;
+        }
+        Some(item)
+    }
+
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.items.len() == self.size
+    }
+
+    /// How many different items the window holds
+    pub fn distinct(&self) -> usize {
+        self.distinct
+    }
+
+    /// Whether the window is full and no item appears in it twice
+    pub fn all_distinct(&self) -> bool {
+        self.is_full() && self.distinct == self.size
+    }
+
+    /// From the oldest to the newest
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &T> + ExactSizeIterator {
+        self.items.iter()
+    }
+}
+
+/// Slides `window` over `items`, outputting how many items had been pushed each time the last
+/// `window.size()` were all different
+pub fn distinct_runs<T, C: Tally<T>>(
+    items: impl IntoIterator<Item = T>,
+    mut window: DistinctWindow<T, C>,
+) -> impl Iterator<Item = usize> {
+    items.into_iter().enumerate().filter_map(move |(i, item)| {
+        window.push(item);
+        window.all_distinct().then_some(i + 1)
+    })
+}